@@ -0,0 +1,159 @@
+use core::fmt;
+use plist::{Dictionary, Value};
+
+/// Directories of an app bundle that hold nested code, with what they hold.
+const COMPONENT_DIRS: &[(&str, &str, ComponentKind)] = &[
+    ("Frameworks/", ".framework", ComponentKind::Framework),
+    ("Frameworks/", ".dylib", ComponentKind::Dylib),
+    ("PlugIns/", ".appex", ComponentKind::AppExtension),
+    ("Extensions/", ".appex", ComponentKind::ExtensionKitExtension),
+    ("Watch/", ".app", ComponentKind::WatchApp),
+    ("AppClips/", ".app", ComponentKind::AppClip),
+];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ComponentKind {
+    Framework,
+    Dylib,
+    AppExtension,
+    ExtensionKitExtension,
+    WatchApp,
+    AppClip,
+}
+
+impl ComponentKind {
+    /// Whether App Store Connect requires the component's version to match the host app's.
+    pub fn versioned_with_host(&self) -> bool {
+        !matches!(self, ComponentKind::Framework | ComponentKind::Dylib)
+    }
+}
+
+impl fmt::Display for ComponentKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            ComponentKind::Framework => "framework",
+            ComponentKind::Dylib => "dylib",
+            ComponentKind::AppExtension => "app extension",
+            ComponentKind::ExtensionKitExtension => "ExtensionKit extension",
+            ComponentKind::WatchApp => "watch app",
+            ComponentKind::AppClip => "App Clip",
+        })
+    }
+}
+
+/// Frameworks, dylibs, extensions and nested apps embedded in an IPA's app bundle.
+#[derive(Clone, Debug, Default)]
+pub struct BundleInventory {
+    /// `CFBundleShortVersionString` and `CFBundleVersion` of the host app.
+    pub version: Option<String>,
+    pub build_number: Option<String>,
+    pub components: Vec<BundleComponent>,
+}
+
+#[derive(Clone, Debug)]
+pub struct BundleComponent {
+    /// Path relative to the app bundle, e.g. `PlugIns/Share.appex`.
+    pub path: String,
+    pub kind: ComponentKind,
+    pub bundle_id: Option<String>,
+    pub version: Option<String>,
+    pub build_number: Option<String>,
+    /// `NSExtensionPointIdentifier`, or `EXExtensionPointIdentifier` for ExtensionKit.
+    pub extension_point: Option<String>,
+    /// Uncompressed size of all files of the component.
+    pub size: u64,
+}
+
+impl BundleComponent {
+    /// The component directory (or dylib) that `path`, relative to the app
+    /// bundle, belongs to.
+    pub fn root_of(path: &str) -> Option<(String, ComponentKind)> {
+        COMPONENT_DIRS.iter().find_map(|(dir, suffix, kind)| {
+            let rest = path.strip_prefix(dir)?;
+            let name = rest.split('/').next()?;
+            let nested = rest.len() > name.len();
+            // Dylibs are files, bundles are directories.
+            let is_bundle = *kind != ComponentKind::Dylib;
+            (name.ends_with(suffix) && nested == is_bundle).then(|| (format!("{}{}", dir, name), *kind))
+        })
+    }
+
+    pub fn new(path: String, kind: ComponentKind) -> BundleComponent {
+        BundleComponent {
+            path,
+            kind,
+            bundle_id: None,
+            version: None,
+            build_number: None,
+            extension_point: None,
+            size: 0,
+        }
+    }
+
+    /// Fills in identifiers from the component's own Info.plist.
+    pub fn read_info_plist(&mut self, plist: &Dictionary) {
+        let string = |dict: &Dictionary, key: &str| dict.get(key)
+            .and_then(Value::as_string)
+            .map(ToString::to_string);
+        self.bundle_id = string(plist, "CFBundleIdentifier");
+        self.version = string(plist, "CFBundleShortVersionString");
+        self.build_number = string(plist, "CFBundleVersion");
+        self.extension_point = plist.get("NSExtension")
+            .or_else(|| plist.get("EXAppExtensionAttributes"))
+            .and_then(Value::as_dictionary)
+            .and_then(|ext| string(ext, "NSExtensionPointIdentifier").or_else(|| string(ext, "EXExtensionPointIdentifier")));
+    }
+}
+
+impl BundleInventory {
+    /// Extensions and nested apps whose version or build number differ from the host app's.
+    pub fn version_mismatches(&self) -> Vec<String> {
+        let mut errors = vec![];
+        for component in self.components.iter().filter(|c| c.kind.versioned_with_host()) {
+            let checks = [
+                ("CFBundleShortVersionString", &component.version, &self.version),
+                ("CFBundleVersion", &component.build_number, &self.build_number),
+            ];
+            for (key, value, host) in checks {
+                if value != host {
+                    errors.push(format!(
+                        "{}: {} {} does not match the app's {}",
+                        component.path, key, value.as_deref().unwrap_or("(missing)"), host.as_deref().unwrap_or("(missing)")
+                    ));
+                }
+            }
+        }
+        errors
+    }
+}
+
+impl fmt::Display for BundleInventory {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for component in &self.components {
+            writeln!(f, "{}", component)?;
+        }
+        for err in self.version_mismatches() {
+            writeln!(f, "ERROR: {}", err)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for BundleComponent {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}: {}", self.path, self.kind)?;
+        if let Some(bundle_id) = &self.bundle_id {
+            write!(f, " {}", bundle_id)?;
+        }
+        if let Some(version) = &self.version {
+            write!(f, " {}", version)?;
+        }
+        if let Some(build_number) = &self.build_number {
+            write!(f, " ({})", build_number)?;
+        }
+        if let Some(extension_point) = &self.extension_point {
+            write!(f, " [{}]", extension_point)?;
+        }
+        write!(f, ", {} bytes", self.size)
+    }
+}
@@ -0,0 +1,90 @@
+use crate::error::{Error, ExtResult};
+
+/// Bounds checked cursor over a byte slice, shared by the binary format parsers.
+#[derive(Clone)]
+pub(crate) struct ByteReader<'a> {
+    buf: &'a [u8],
+    pos: usize,
+    big_endian: bool,
+}
+
+impl<'a> ByteReader<'a> {
+    pub fn at(buf: &'a [u8], pos: usize) -> Self {
+        Self { buf, pos, big_endian: false }
+    }
+
+    /// Reads multi-byte integers as big-endian from now on.
+    pub fn big_endian(mut self) -> Self {
+        self.big_endian = true;
+        self
+    }
+
+    pub fn pos(&self) -> usize {
+        self.pos
+    }
+
+    pub fn remaining(&self) -> usize {
+        self.buf.len().saturating_sub(self.pos)
+    }
+
+    pub fn seek(&mut self, pos: usize) -> ExtResult<()> {
+        if pos > self.buf.len() {
+            return Err(Error::UnexpectedEof);
+        }
+        self.pos = pos;
+        Ok(())
+    }
+
+    pub fn skip(&mut self, n: usize) -> ExtResult<()> {
+        self.seek(self.pos.checked_add(n).ok_or(Error::UnexpectedEof)?)
+    }
+
+    pub fn bytes(&mut self, n: usize) -> ExtResult<&'a [u8]> {
+        let end = self.pos.checked_add(n).ok_or(Error::UnexpectedEof)?;
+        let out = self.buf.get(self.pos..end).ok_or(Error::UnexpectedEof)?;
+        self.pos = end;
+        Ok(out)
+    }
+
+    fn array<const N: usize>(&mut self) -> ExtResult<[u8; N]> {
+        let mut out = [0u8; N];
+        out.copy_from_slice(self.bytes(N)?);
+        Ok(out)
+    }
+
+    pub fn u8(&mut self) -> ExtResult<u8> {
+        Ok(self.array::<1>()?[0])
+    }
+
+    pub fn u16(&mut self) -> ExtResult<u16> {
+        let b = self.array()?;
+        Ok(if self.big_endian { u16::from_be_bytes(b) } else { u16::from_le_bytes(b) })
+    }
+
+    pub fn u32(&mut self) -> ExtResult<u32> {
+        let b = self.array()?;
+        Ok(if self.big_endian { u32::from_be_bytes(b) } else { u32::from_le_bytes(b) })
+    }
+
+    pub fn u64(&mut self) -> ExtResult<u64> {
+        let b = self.array()?;
+        Ok(if self.big_endian { u64::from_be_bytes(b) } else { u64::from_le_bytes(b) })
+    }
+
+    /// Unsigned LEB128, as used by DEX files.
+    pub fn uleb128(&mut self) -> ExtResult<u32> {
+        let mut value = 0u32;
+        for shift in (0..35).step_by(7) {
+            let b = self.u8()?;
+            value |= u32::from(b & 0x7f) << shift;
+            if b & 0x80 == 0 {
+                break;
+            }
+        }
+        Ok(value)
+    }
+}
+
+pub(crate) fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
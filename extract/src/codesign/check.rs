@@ -0,0 +1,109 @@
+use core::fmt;
+use plist::Value;
+use crate::date::format_timestamp;
+use super::{format_value, IpaSigningInfo};
+
+/// Entitlements every profile grants implicitly.
+const IMPLICIT_ENTITLEMENTS: &[&str] = &["com.apple.developer.team-identifier"];
+
+/// Whether the signature, entitlements and bundle ID agree with the embedded profile.
+#[derive(Clone, Debug, Default)]
+pub struct ProfileCheck {
+    pub errors: Vec<String>,
+}
+
+impl ProfileCheck {
+    pub fn passed(&self) -> bool {
+        self.errors.is_empty()
+    }
+}
+
+impl IpaSigningInfo {
+    /// Checks the executable's entitlements against those the profile grants,
+    /// the bundle ID against the profile's app ID, and profile and developer
+    /// certificate expiry at unix time `now`.
+    pub fn check(&self, now: i64) -> ProfileCheck {
+        let mut errors = vec![];
+        let profile = match &self.profile {
+            Some(profile) => profile,
+            None => {
+                errors.push("no embedded provisioning profile".to_string());
+                return ProfileCheck { errors };
+            }
+        };
+
+        if profile.expiration_date < now {
+            errors.push(format!("profile expired on {}", format_timestamp(profile.expiration_date)));
+        }
+        for cert in &profile.certificates {
+            if cert.not_after < now {
+                errors.push(format!("developer certificate {} expired on {}", cert.subject, format_timestamp(cert.not_after)));
+            }
+        }
+
+        let app_id = profile.entitlements
+            .get("application-identifier")
+            .and_then(Value::as_string);
+        if let (Some(app_id), Some(bundle_id)) = (app_id, &self.bundle_id) {
+            let pattern = app_id.split_once('.').map_or(app_id, |(_, pattern)| pattern);
+            if !wildcard_match(pattern, bundle_id) {
+                errors.push(format!("bundle ID {} does not match the profile app ID {}", bundle_id, app_id));
+            }
+        }
+
+        if self.code_signatures.is_empty() {
+            errors.push("main executable is not code signed".to_string());
+        }
+        for signature in &self.code_signatures {
+            if let Some(team_id) = &signature.team_id {
+                if *team_id != profile.team_id {
+                    errors.push(format!("{}: signed by team {}, the profile belongs to {}", signature.arch, team_id, profile.team_id));
+                }
+            }
+            for (key, value) in &signature.entitlements {
+                if IMPLICIT_ENTITLEMENTS.contains(&key.as_str()) {
+                    continue;
+                }
+                match profile.entitlements.get(key) {
+                    Some(granted) if grants(granted, value) => {}
+                    Some(granted) => errors.push(format!(
+                        "{}: entitlement {} = {} exceeds the profile's {}",
+                        signature.arch, key, format_value(value), format_value(granted)
+                    )),
+                    None => errors.push(format!("{}: entitlement {} is not granted by the profile", signature.arch, key)),
+                }
+            }
+        }
+
+        ProfileCheck { errors }
+    }
+}
+
+/// Whether a profile entitlement value allows the executable's value. Profiles
+/// may use `*` wildcards in strings and grant a list of allowed values.
+fn grants(granted: &Value, requested: &Value) -> bool {
+    match (granted, requested) {
+        (Value::Boolean(granted), Value::Boolean(requested)) => *granted || !*requested,
+        (Value::String(pattern), Value::String(value)) => wildcard_match(pattern, value),
+        (Value::Array(allowed), Value::String(_)) => allowed.iter().any(|a| grants(a, requested)),
+        (_, Value::Array(values)) => values.iter().all(|v| grants(granted, v)),
+        (granted, requested) => granted == requested,
+    }
+}
+
+fn wildcard_match(pattern: &str, value: &str) -> bool {
+    match pattern.strip_suffix('*') {
+        Some(prefix) => value.starts_with(prefix),
+        None => pattern == value,
+    }
+}
+
+impl fmt::Display for ProfileCheck {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "Consistent with provisioning profile: {}", self.passed())?;
+        for err in &self.errors {
+            writeln!(f, "ERROR: {}", err)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,56 @@
+use core::fmt;
+use plist::Value;
+
+mod check;
+mod profile;
+mod signature;
+
+/// How an IPA is signed and provisioned.
+#[derive(Clone, Debug, Default)]
+pub struct IpaSigningInfo {
+    /// `CFBundleIdentifier` of the app bundle.
+    pub bundle_id: Option<String>,
+    pub profile: Option<ProvisioningProfile>,
+    /// Code signature of each architecture of the main executable.
+    pub code_signatures: Vec<CodeSignature>,
+}
+
+/// Compact one-line form of a plist value, for entitlements.
+pub fn format_value(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        Value::Boolean(b) => b.to_string(),
+        Value::Integer(i) => i.to_string(),
+        Value::Real(r) => r.to_string(),
+        Value::Array(items) => format!("[{}]", items.iter().map(format_value).collect::<Vec<String>>().join(", ")),
+        Value::Dictionary(dict) => format!(
+            "{{{}}}",
+            dict.iter()
+                .map(|(k, v)| format!("{}: {}", k, format_value(v)))
+                .collect::<Vec<String>>()
+                .join(", ")
+        ),
+        Value::Data(data) => base64::encode(data),
+        other => format!("{:?}", other),
+    }
+}
+
+impl fmt::Display for IpaSigningInfo {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.profile {
+            Some(profile) => write!(f, "{}", profile)?,
+            None => writeln!(f, "No embedded provisioning profile")?,
+        }
+        if self.code_signatures.is_empty() {
+            writeln!(f, "Main executable is not code signed")?;
+        }
+        for signature in &self.code_signatures {
+            write!(f, "{}", signature)?;
+        }
+        Ok(())
+    }
+}
+
+pub use check::ProfileCheck;
+pub use profile::{ProvisioningProfile, DistributionType};
+pub use signature::{CodeSignature, CdHash};
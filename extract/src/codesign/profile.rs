@@ -0,0 +1,145 @@
+use core::fmt;
+use serde::Deserialize;
+use plist::{Date, Dictionary, Value};
+use crate::{
+    date::{format_timestamp, unix_timestamp},
+    error::{Error, ExtResult},
+    pkcs7::SignedData,
+    signing::Certificate,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DistributionType {
+    Development,
+    AdHoc,
+    Enterprise,
+    AppStore,
+}
+
+/// The plist inside the CMS envelope of a `.mobileprovision` file.
+#[derive(Deserialize, Clone, Debug)]
+struct ProfilePlist {
+    #[serde(rename = "Name")]
+    name: String,
+    #[serde(rename = "UUID")]
+    uuid: String,
+    #[serde(rename = "TeamIdentifier", default)]
+    team_identifier: Vec<String>,
+    #[serde(rename = "TeamName", default)]
+    team_name: String,
+    #[serde(rename = "AppIDName", default)]
+    app_id_name: String,
+    #[serde(rename = "ApplicationIdentifierPrefix", default)]
+    app_id_prefix: Vec<String>,
+    #[serde(rename = "CreationDate")]
+    creation_date: Date,
+    #[serde(rename = "ExpirationDate")]
+    expiration_date: Date,
+    #[serde(rename = "Platform", default)]
+    platforms: Vec<String>,
+    #[serde(rename = "ProvisionedDevices")]
+    provisioned_devices: Option<Vec<String>>,
+    #[serde(rename = "ProvisionsAllDevices", default)]
+    provisions_all_devices: bool,
+    #[serde(rename = "DeveloperCertificates", default)]
+    developer_certificates: Vec<Value>,
+    #[serde(rename = "Entitlements", default)]
+    entitlements: Dictionary,
+}
+
+/// A provisioning profile, as embedded in an app bundle.
+#[derive(Clone, Debug)]
+pub struct ProvisioningProfile {
+    pub name: String,
+    pub uuid: String,
+    pub team_id: String,
+    pub team_name: String,
+    pub app_id_name: String,
+    pub app_id_prefix: Vec<String>,
+    /// Unix timestamps.
+    pub creation_date: i64,
+    pub expiration_date: i64,
+    pub distribution: DistributionType,
+    pub platforms: Vec<String>,
+    /// UDIDs of the devices a development or ad-hoc profile installs on.
+    pub devices: Vec<String>,
+    pub certificates: Vec<Certificate>,
+    /// Entitlements the profile grants.
+    pub entitlements: Dictionary,
+}
+
+impl ProvisioningProfile {
+    /// Parses a `.mobileprovision` file: a CMS SignedData wrapping an XML plist.
+    pub fn from_buffer(buf: &[u8]) -> ExtResult<ProvisioningProfile> {
+        let content = SignedData::parse(buf)?
+            .content
+            .ok_or_else(|| Error::InvalidDer("profile has no signed content".into()))?;
+        let plist = plist::from_bytes::<ProfilePlist>(&content)?;
+
+        let get_task_allow = plist.entitlements
+            .get("get-task-allow")
+            .and_then(Value::as_boolean)
+            .unwrap_or(false);
+        let distribution = match (&plist.provisioned_devices, plist.provisions_all_devices) {
+            (_, true) => DistributionType::Enterprise,
+            (Some(_), false) if get_task_allow => DistributionType::Development,
+            (Some(_), false) => DistributionType::AdHoc,
+            (None, false) => DistributionType::AppStore,
+        };
+        let certificates = plist.developer_certificates
+            .iter()
+            .filter_map(Value::as_data)
+            .map(Certificate::from_der)
+            .collect::<ExtResult<Vec<Certificate>>>()?;
+
+        Ok(ProvisioningProfile {
+            name: plist.name,
+            uuid: plist.uuid,
+            team_id: plist.team_identifier.into_iter().next().unwrap_or_default(),
+            team_name: plist.team_name,
+            app_id_name: plist.app_id_name,
+            app_id_prefix: plist.app_id_prefix,
+            creation_date: unix_timestamp(plist.creation_date.into()),
+            expiration_date: unix_timestamp(plist.expiration_date.into()),
+            distribution,
+            platforms: plist.platforms,
+            devices: plist.provisioned_devices.unwrap_or_default(),
+            certificates,
+            entitlements: plist.entitlements,
+        })
+    }
+}
+
+impl fmt::Display for DistributionType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            DistributionType::Development => "development",
+            DistributionType::AdHoc => "ad-hoc",
+            DistributionType::Enterprise => "enterprise",
+            DistributionType::AppStore => "app store",
+        })
+    }
+}
+
+impl fmt::Display for ProvisioningProfile {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "Profile name: {}", self.name)?;
+        writeln!(f, "Profile UUID: {}", self.uuid)?;
+        writeln!(f, "Team: {} ({})", self.team_name, self.team_id)?;
+        writeln!(f, "App ID name: {}", self.app_id_name)?;
+        writeln!(f, "App ID prefix: {}", self.app_id_prefix.join(", "))?;
+        writeln!(f, "Platforms: {}", self.platforms.join(", "))?;
+        writeln!(f, "Created: {}", format_timestamp(self.creation_date))?;
+        writeln!(f, "Expires: {}", format_timestamp(self.expiration_date))?;
+        writeln!(f, "Distribution: {}", self.distribution)?;
+        for udid in &self.devices {
+            writeln!(f, "Provisioned device: {}", udid)?;
+        }
+        for (i, cert) in self.certificates.iter().enumerate() {
+            for line in cert.to_string().lines() {
+                writeln!(f, "Developer certificate #{} {}", i + 1, line)?;
+            }
+        }
+        Ok(())
+    }
+}
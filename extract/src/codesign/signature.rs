@@ -0,0 +1,228 @@
+use core::fmt;
+use plist::{Dictionary, Value};
+use crate::{
+    bytes::{to_hex, ByteReader},
+    der::{DerReader, Tlv, TAG_INTEGER, TAG_SEQUENCE},
+    error::{Error, ExtResult},
+    macho::{MachO, LC_CODE_SIGNATURE},
+    signing::crypto::DigestAlgorithm,
+};
+use super::format_value;
+
+const CSMAGIC_EMBEDDED_SIGNATURE: u32 = 0xfade_0cc0;
+const CSMAGIC_CODEDIRECTORY: u32 = 0xfade_0c02;
+const CSMAGIC_EMBEDDED_ENTITLEMENTS: u32 = 0xfade_7171;
+const CSMAGIC_EMBEDDED_DER_ENTITLEMENTS: u32 = 0xfade_7172;
+const CSMAGIC_BLOBWRAPPER: u32 = 0xfade_0b01;
+
+const CS_SUPPORTSTEAMID: u32 = 0x20200;
+/// CDHashes are truncated to the length of a SHA-1 digest.
+const CDHASH_LEN: usize = 20;
+
+const TAG_BOOLEAN: u8 = 0x01;
+const TAG_UTF8_STRING: u8 = 0x0c;
+/// `[APPLICATION 16]`, wrapping DER entitlements.
+const TAG_DER_ENTITLEMENTS: u8 = 0x70;
+/// `[CONTEXT 16]`, a dictionary in DER entitlements.
+const TAG_DER_DICTIONARY: u8 = 0xb0;
+/// Nesting of arrays and dictionaries past which entitlements are rejected,
+/// so a crafted signature cannot exhaust the stack.
+const DER_MAX_DEPTH: usize = 64;
+
+/// Hash of one code directory, which identifies the signed code.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CdHash {
+    pub hash_type: String,
+    pub hash: String,
+}
+
+/// The embedded code signature of one Mach-O architecture.
+#[derive(Clone, Debug, Default)]
+pub struct CodeSignature {
+    pub arch: String,
+    pub identifier: String,
+    pub team_id: Option<String>,
+    /// The primary code directory first, then any alternate ones.
+    pub cdhashes: Vec<CdHash>,
+    /// Entitlements as embedded in the XML blob, or decoded from the DER one.
+    pub entitlements: Dictionary,
+    pub entitlements_xml: Option<String>,
+    pub entitlements_der: Option<Vec<u8>>,
+    /// Whether the signature carries a CMS signature, ad-hoc signatures don't.
+    pub has_cms: bool,
+}
+
+impl CodeSignature {
+    /// Reads the `LC_CODE_SIGNATURE` superblob of `macho`, if it is signed.
+    pub(crate) fn from_macho(macho: &MachO) -> ExtResult<Option<CodeSignature>> {
+        let command = match macho.command(LC_CODE_SIGNATURE) {
+            Some(command) => command,
+            None => return Ok(None),
+        };
+        let mut r = ByteReader::at(command.data, 8);
+        let offset = r.u32()? as usize;
+        let size = r.u32()? as usize;
+        let blob = offset.checked_add(size)
+            .and_then(|end| macho.data.get(offset..end))
+            .ok_or(Error::UnexpectedEof)?;
+
+        let mut signature = CodeSignature::parse(blob)?;
+        signature.arch = macho.arch();
+        Ok(Some(signature))
+    }
+
+    /// Parses an embedded signature superblob.
+    pub fn parse(blob: &[u8]) -> ExtResult<CodeSignature> {
+        let mut r = ByteReader::at(blob, 0).big_endian();
+        if r.u32()? != CSMAGIC_EMBEDDED_SIGNATURE {
+            return Err(invalid("not an embedded signature"));
+        }
+        let _length = r.u32()?;
+        let count = r.u32()?;
+
+        let mut signature = CodeSignature::default();
+        for _ in 0..count {
+            let _slot = r.u32()?;
+            let offset = r.u32()? as usize;
+            let mut b = ByteReader::at(blob, offset).big_endian();
+            let magic = b.u32()?;
+            let length = b.u32()? as usize;
+            // The length covers the magic and length fields themselves.
+            if length < 8 {
+                return Err(invalid("blob shorter than its header"));
+            }
+            let data = offset.checked_add(length)
+                .and_then(|end| blob.get(offset..end))
+                .ok_or(Error::UnexpectedEof)?;
+
+            match magic {
+                CSMAGIC_CODEDIRECTORY => signature.read_code_directory(data)?,
+                CSMAGIC_EMBEDDED_ENTITLEMENTS => {
+                    signature.entitlements_xml = Some(String::from_utf8_lossy(&data[8..]).into_owned());
+                    signature.entitlements = plist::from_bytes(&data[8..])?;
+                }
+                CSMAGIC_EMBEDDED_DER_ENTITLEMENTS => signature.entitlements_der = Some(data[8..].to_vec()),
+                CSMAGIC_BLOBWRAPPER => signature.has_cms = length > 8,
+                _ => {}
+            }
+        }
+
+        if signature.entitlements_xml.is_none() {
+            if let Some(der) = &signature.entitlements_der {
+                signature.entitlements = decode_der_entitlements(der)?;
+            }
+        }
+
+        Ok(signature)
+    }
+
+    fn read_code_directory(&mut self, data: &[u8]) -> ExtResult<()> {
+        let mut r = ByteReader::at(data, 8).big_endian();
+        let version = r.u32()?;
+        let _flags = r.u32()?;
+        let _hash_offset = r.u32()?;
+        let ident_offset = r.u32()? as usize;
+        let _special_slots = r.u32()?;
+        let _code_slots = r.u32()?;
+        let _code_limit = r.u32()?;
+        let _hash_size = r.u8()?;
+        let hash_type = r.u8()?;
+
+        let (name, algorithm) = match hash_type {
+            1 => ("SHA-1", DigestAlgorithm::Sha1),
+            2 => ("SHA-256", DigestAlgorithm::Sha256),
+            3 => ("SHA-256 (truncated)", DigestAlgorithm::Sha256),
+            4 => ("SHA-384", DigestAlgorithm::Sha384),
+            _ => return Err(invalid("unknown code directory hash type")),
+        };
+        let mut hash = algorithm.digest(data);
+        hash.truncate(CDHASH_LEN);
+        self.cdhashes.push(CdHash { hash_type: name.to_string(), hash: to_hex(&hash) });
+
+        // Alternate code directories repeat the identifier and team.
+        if self.cdhashes.len() == 1 {
+            self.identifier = c_string(data, ident_offset);
+            if version >= CS_SUPPORTSTEAMID {
+                r.seek(0x30)?;
+                let team_offset = r.u32()? as usize;
+                if team_offset != 0 {
+                    self.team_id = Some(c_string(data, team_offset));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// The primary CDHash, which is what `codesign` reports.
+    pub fn cdhash(&self) -> Option<&CdHash> {
+        self.cdhashes.first()
+    }
+}
+
+fn c_string(buf: &[u8], offset: usize) -> String {
+    let bytes = buf.get(offset..).unwrap_or_default();
+    let end = bytes.iter().position(|b| *b == 0).unwrap_or(bytes.len());
+    String::from_utf8_lossy(&bytes[..end]).into_owned()
+}
+
+/// Decodes the DER entitlements format: `[APPLICATION 16] { version, [16] dictionary }`.
+fn decode_der_entitlements(der: &[u8]) -> ExtResult<Dictionary> {
+    let mut outer = DerReader::new(der).expect(TAG_DER_ENTITLEMENTS)?.children();
+    let _version = outer.expect(TAG_INTEGER)?;
+    match der_value(outer.expect(TAG_DER_DICTIONARY)?, 0)? {
+        Value::Dictionary(dict) => Ok(dict),
+        _ => Err(invalid("entitlements are not a dictionary")),
+    }
+}
+
+fn der_value(tlv: Tlv, depth: usize) -> ExtResult<Value> {
+    if depth > DER_MAX_DEPTH {
+        return Err(invalid("DER entitlements are nested too deeply"));
+    }
+    Ok(match tlv.tag {
+        TAG_BOOLEAN => Value::Boolean(tlv.content.iter().any(|b| *b != 0)),
+        TAG_INTEGER => {
+            let sign = if tlv.content.first().is_some_and(|b| b & 0x80 != 0) { -1i64 } else { 0 };
+            Value::Integer(tlv.content.iter().fold(sign, |acc, b| (acc << 8) | *b as i64).into())
+        }
+        TAG_UTF8_STRING => Value::String(String::from_utf8_lossy(tlv.content).into_owned()),
+        TAG_SEQUENCE => {
+            let mut items = tlv.children();
+            let mut array = vec![];
+            while !items.is_empty() {
+                array.push(der_value(items.next()?, depth + 1)?);
+            }
+            Value::Array(array)
+        }
+        TAG_DER_DICTIONARY => {
+            let mut entries = tlv.children();
+            let mut dict = Dictionary::new();
+            while !entries.is_empty() {
+                let mut entry = entries.expect(TAG_SEQUENCE)?.children();
+                let key = entry.expect(TAG_UTF8_STRING)?;
+                dict.insert(String::from_utf8_lossy(key.content).into_owned(), der_value(entry.next()?, depth + 1)?);
+            }
+            Value::Dictionary(dict)
+        }
+        tag => return Err(invalid(&format!("unexpected DER entitlements tag 0x{:02x}", tag))),
+    })
+}
+
+fn invalid(msg: &str) -> Error {
+    Error::InvalidCodeSignature(msg.to_string())
+}
+
+impl fmt::Display for CodeSignature {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "Code signature ({}) identifier: {}", self.arch, self.identifier)?;
+        writeln!(f, "Code signature ({}) Team ID: {}", self.arch, self.team_id.as_deref().unwrap_or("not set"))?;
+        writeln!(f, "Code signature ({}) signed: {}", self.arch, if self.has_cms { "yes" } else { "ad-hoc" })?;
+        for cdhash in &self.cdhashes {
+            writeln!(f, "Code signature ({}) CDHash {}: {}", self.arch, cdhash.hash_type, cdhash.hash)?;
+        }
+        for (key, value) in &self.entitlements {
+            writeln!(f, "Code signature ({}) entitlement {}: {}", self.arch, key, format_value(value))?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,159 @@
+use crate::error::{Error, ExtResult};
+
+pub const TAG_INTEGER: u8 = 0x02;
+pub const TAG_OCTET_STRING: u8 = 0x04;
+pub const TAG_OCTET_STRING_CONSTRUCTED: u8 = 0x24;
+pub const TAG_OID: u8 = 0x06;
+pub const TAG_SEQUENCE: u8 = 0x30;
+pub const TAG_SET: u8 = 0x31;
+pub const TAG_CONTEXT_0: u8 = 0xa0;
+pub const TAG_CONTEXT_1: u8 = 0xa1;
+
+/// One BER/DER element. `raw` covers the whole encoding, `content` only the value.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct Tlv<'a> {
+    pub tag: u8,
+    pub content: &'a [u8],
+    pub raw: &'a [u8],
+}
+
+impl<'a> Tlv<'a> {
+    pub fn children(&self) -> DerReader<'a> {
+        DerReader::new(self.content)
+    }
+
+    /// Value of an OCTET STRING, joining the segments of a BER constructed one.
+    pub fn octets(&self) -> ExtResult<Vec<u8>> {
+        match self.tag {
+            TAG_OCTET_STRING => Ok(self.content.to_vec()),
+            TAG_OCTET_STRING_CONSTRUCTED => {
+                let mut value = vec![];
+                let mut segments = self.children();
+                while !segments.is_empty() {
+                    value.extend(segments.next()?.octets()?);
+                }
+                Ok(value)
+            }
+            _ => Err(invalid("expected an octet string")),
+        }
+    }
+
+    /// Dotted form of an OBJECT IDENTIFIER.
+    pub fn oid(&self) -> ExtResult<String> {
+        if self.tag != TAG_OID || self.content.is_empty() {
+            return Err(invalid("expected an object identifier"));
+        }
+        let mut parts: Vec<u64> = vec![];
+        let mut value: u64 = 0;
+        for (i, b) in self.content.iter().enumerate() {
+            value = (value << 7) | (b & 0x7f) as u64;
+            if b & 0x80 == 0 {
+                if parts.is_empty() {
+                    let first = (value / 40).min(2);
+                    parts.push(first);
+                    parts.push(value - first * 40);
+                } else {
+                    parts.push(value);
+                }
+                value = 0;
+            } else if i == self.content.len() - 1 {
+                return Err(invalid("truncated object identifier"));
+            }
+        }
+        Ok(parts.iter().map(u64::to_string).collect::<Vec<String>>().join("."))
+    }
+}
+
+#[derive(Clone, Copy)]
+pub(crate) struct DerReader<'a> {
+    buf: &'a [u8],
+}
+
+impl<'a> DerReader<'a> {
+    pub fn new(buf: &'a [u8]) -> Self {
+        Self { buf }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.buf.is_empty()
+    }
+
+    pub fn peek_tag(&self) -> Option<u8> {
+        self.buf.first().copied()
+    }
+
+    pub fn next(&mut self) -> ExtResult<Tlv<'a>> {
+        let tlv = read_tlv(self.buf, 0)?;
+        self.buf = &self.buf[tlv.raw.len()..];
+        Ok(tlv)
+    }
+
+    /// Reads the next element, failing unless it has `tag`.
+    pub fn expect(&mut self, tag: u8) -> ExtResult<Tlv<'a>> {
+        let tlv = self.next()?;
+        if tlv.tag != tag {
+            return Err(invalid(&format!("expected tag 0x{:02x}, found 0x{:02x}", tag, tlv.tag)));
+        }
+        Ok(tlv)
+    }
+
+    /// Reads the next element only if it has `tag`.
+    pub fn optional(&mut self, tag: u8) -> ExtResult<Option<Tlv<'a>>> {
+        if self.peek_tag() == Some(tag) {
+            self.next().map(Some)
+        } else {
+            Ok(None)
+        }
+    }
+}
+
+/// Nesting of indefinite-length elements allowed before giving up, so crafted
+/// input cannot exhaust the stack.
+const MAX_INDEFINITE_DEPTH: usize = 64;
+
+fn read_tlv(buf: &[u8], depth: usize) -> ExtResult<Tlv<'_>> {
+    let tag = *buf.first().ok_or(Error::UnexpectedEof)?;
+    if tag & 0x1f == 0x1f {
+        return Err(invalid("high tag numbers are not supported"));
+    }
+    let first = *buf.get(1).ok_or(Error::UnexpectedEof)? as usize;
+
+    if first == 0x80 {
+        // Indefinite length: children up to an end-of-contents marker.
+        if tag & 0x20 == 0 {
+            return Err(invalid("indefinite length on a primitive element"));
+        }
+        if depth >= MAX_INDEFINITE_DEPTH {
+            return Err(invalid("indefinite length elements nested too deeply"));
+        }
+        let mut pos = 2;
+        loop {
+            let rest = buf.get(pos..).ok_or(Error::UnexpectedEof)?;
+            if rest.starts_with(&[0, 0]) {
+                return Ok(Tlv { tag, content: &buf[2..pos], raw: &buf[..pos + 2] });
+            }
+            pos += read_tlv(rest, depth + 1)?.raw.len();
+        }
+    }
+
+    let (len, header) = if first < 0x80 {
+        (first, 2)
+    } else {
+        let n = first & 0x7f;
+        if n > 4 {
+            return Err(invalid("length too large"));
+        }
+        let bytes = buf.get(2..2 + n).ok_or(Error::UnexpectedEof)?;
+        (bytes.iter().fold(0usize, |acc, b| (acc << 8) | *b as usize), 2 + n)
+    };
+    let end = header.checked_add(len).ok_or(Error::UnexpectedEof)?;
+    if end > buf.len() {
+        return Err(Error::UnexpectedEof);
+    }
+
+    Ok(Tlv { tag, content: &buf[header..end], raw: &buf[..end] })
+}
+
+fn invalid(msg: &str) -> Error {
+    Error::InvalidDer(msg.to_string())
+}
@@ -0,0 +1,130 @@
+mod multidex;
+
+use std::collections::BTreeMap;
+use crate::{
+    bytes::ByteReader,
+    error::{Error, ExtResult},
+};
+
+const DEX_MAGIC: &[u8] = b"dex\n";
+const ENDIAN_CONSTANT: u32 = 0x12345678;
+const HEADER_SIZE: usize = 0x70;
+
+/// Method and field references a single DEX file can hold.
+pub const DEX_REFERENCE_LIMIT: u32 = 65536;
+
+/// Header counts of a `classes*.dex` file and its method references by package.
+#[derive(Clone, Debug, Default)]
+pub struct DexFile {
+    /// Path inside the APK.
+    pub path: String,
+    /// Format version, such as `035` or `039`.
+    pub version: String,
+    pub string_count: u32,
+    pub type_count: u32,
+    pub field_count: u32,
+    /// Method references, the count the 64K limit applies to.
+    pub method_count: u32,
+    /// Classes defined in the file.
+    pub class_count: u32,
+    /// Method references by the package of their class, `""` for the default package.
+    pub package_methods: BTreeMap<String, u32>,
+}
+
+impl DexFile {
+    pub fn parse(path: &str, buf: &[u8]) -> ExtResult<DexFile> {
+        if !buf.starts_with(DEX_MAGIC) || buf.len() < HEADER_SIZE {
+            return Err(invalid("not a DEX file"));
+        }
+        let version = String::from_utf8_lossy(&buf[4..7]).into_owned();
+
+        let mut r = ByteReader::at(buf, 0x28);
+        if r.u32()? != ENDIAN_CONSTANT {
+            return Err(invalid("unsupported byte order"));
+        }
+        r.seek(0x38)?;
+        let string_count = r.u32()?;
+        let string_ids_off = r.u32()? as usize;
+        let type_count = r.u32()?;
+        let type_ids_off = r.u32()? as usize;
+        r.skip(8)?;
+        let field_count = r.u32()?;
+        let _field_ids_off = r.u32()?;
+        let method_count = r.u32()?;
+        let method_ids_off = r.u32()? as usize;
+        let class_count = r.u32()?;
+
+        let string = |idx: u32| -> ExtResult<String> {
+            if idx >= string_count {
+                return Err(invalid("string index out of range"));
+            }
+            let offset = ByteReader::at(buf, string_ids_off + idx as usize * 4).u32()?;
+            let mut r = ByteReader::at(buf, offset as usize);
+            let _utf16_len = r.uleb128()?;
+            let bytes = &buf[r.pos()..];
+            let end = bytes.iter().position(|b| *b == 0).unwrap_or(bytes.len());
+            Ok(String::from_utf8_lossy(&bytes[..end]).into_owned())
+        };
+
+        // Resolve each referenced class once, as many methods share one.
+        let mut packages: BTreeMap<u16, String> = BTreeMap::new();
+        let mut package_methods: BTreeMap<String, u32> = BTreeMap::new();
+        for i in 0..method_count as usize {
+            let class_idx = ByteReader::at(buf, method_ids_off + i * 8).u16()?;
+            let package = match packages.get(&class_idx) {
+                Some(package) => package.clone(),
+                None => {
+                    if u32::from(class_idx) >= type_count {
+                        return Err(invalid("type index out of range"));
+                    }
+                    let descriptor_idx = ByteReader::at(buf, type_ids_off + class_idx as usize * 4).u32()?;
+                    let package = package_of(&string(descriptor_idx)?);
+                    packages.insert(class_idx, package.clone());
+                    package
+                }
+            };
+            *package_methods.entry(package).or_default() += 1;
+        }
+
+        Ok(DexFile {
+            path: path.to_string(),
+            version,
+            string_count,
+            type_count,
+            field_count,
+            method_count,
+            class_count,
+            package_methods,
+        })
+    }
+
+    /// Method references grouped by the first `depth` segments of their
+    /// package, so `com.google.android.gms.ads` counts toward `com.google`
+    /// at depth 2.
+    pub fn packages(&self, depth: usize) -> BTreeMap<String, u32> {
+        let mut packages: BTreeMap<String, u32> = BTreeMap::new();
+        for (package, count) in &self.package_methods {
+            let prefix = package.split('.').take(depth).collect::<Vec<&str>>().join(".");
+            *packages.entry(prefix).or_default() += count;
+        }
+        packages
+    }
+}
+
+/// The dotted package of a type descriptor such as `[Lcom/example/Foo;`.
+fn package_of(descriptor: &str) -> String {
+    let class = descriptor.trim_start_matches('[')
+        .strip_prefix('L')
+        .and_then(|d| d.strip_suffix(';'))
+        .unwrap_or_default();
+    match class.rsplit_once('/') {
+        Some((package, _)) => package.replace('/', "."),
+        None => String::new(),
+    }
+}
+
+fn invalid(msg: &str) -> Error {
+    Error::InvalidDex(msg.to_string())
+}
+
+pub use multidex::DexFiles;
@@ -0,0 +1,88 @@
+use core::fmt;
+use std::collections::BTreeMap;
+use super::{DexFile, DEX_REFERENCE_LIMIT};
+
+/// The `classes*.dex` files of an APK, in load order.
+#[derive(Clone, Debug, Default)]
+pub struct DexFiles {
+    pub files: Vec<DexFile>,
+}
+
+impl DexFiles {
+    /// The position of a root `classes<N>.dex` entry in the multidex load
+    /// order, where `classes.dex` is 1.
+    pub fn index_of(path: &str) -> Option<u32> {
+        let n = path.strip_prefix("classes")?.strip_suffix(".dex")?;
+        match n {
+            "" => Some(1),
+            n if !n.starts_with('0') => n.parse().ok().filter(|n| *n >= 2),
+            _ => None,
+        }
+    }
+
+    pub fn is_multidex(&self) -> bool {
+        self.files.len() > 1
+    }
+
+    pub fn method_count(&self) -> u32 {
+        self.files.iter().map(|f| f.method_count).sum()
+    }
+
+    pub fn class_count(&self) -> u32 {
+        self.files.iter().map(|f| f.class_count).sum()
+    }
+
+    pub fn field_count(&self) -> u32 {
+        self.files.iter().map(|f| f.field_count).sum()
+    }
+
+    /// Method references of all files by package prefix, largest first.
+    pub fn packages(&self, depth: usize) -> Vec<(String, u32)> {
+        let mut totals: BTreeMap<String, u32> = BTreeMap::new();
+        for file in &self.files {
+            for (package, count) in file.packages(depth) {
+                *totals.entry(package).or_default() += count;
+            }
+        }
+        let mut packages: Vec<(String, u32)> = totals.into_iter().collect();
+        packages.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        packages
+    }
+
+    /// Gaps in the `classes<N>.dex` sequence, past which the runtime stops
+    /// loading, and files at the reference limit.
+    pub fn layout_issues(&self) -> Vec<String> {
+        let mut issues = vec![];
+        for (i, file) in self.files.iter().enumerate() {
+            if DexFiles::index_of(&file.path) != Some(i as u32 + 1) {
+                issues.push(format!("{} follows a gap in the sequence and is not loaded", file.path));
+            }
+            if file.method_count >= DEX_REFERENCE_LIMIT || file.field_count >= DEX_REFERENCE_LIMIT {
+                issues.push(format!("{} is at the 64K reference limit", file.path));
+            }
+        }
+        issues
+    }
+}
+
+impl fmt::Display for DexFiles {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for file in &self.files {
+            writeln!(
+                f, "{}: DEX {}, {} classes, {} methods ({:.1}% of 64K), {} fields, {} strings",
+                file.path, file.version, file.class_count, file.method_count,
+                f64::from(file.method_count) * 100.0 / f64::from(DEX_REFERENCE_LIMIT),
+                file.field_count, file.string_count,
+            )?;
+        }
+        writeln!(
+            f, "Total: {} file(s){}, {} classes, {} methods, {} fields",
+            self.files.len(), if self.is_multidex() { " (multidex)" } else { "" },
+            self.class_count(), self.method_count(), self.field_count(),
+        )?;
+        for issue in self.layout_issues() {
+            writeln!(f, "ERROR: {}", issue)?;
+        }
+        Ok(())
+    }
+}
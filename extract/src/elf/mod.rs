@@ -0,0 +1,180 @@
+mod native;
+mod page_size;
+
+use crate::{
+    bytes::ByteReader,
+    error::{Error, ExtResult},
+};
+
+const ELF_MAGIC: &[u8] = b"\x7fELF";
+const ELFCLASS64: u8 = 2;
+const ELFDATA2MSB: u8 = 2;
+
+pub const PT_LOAD: u32 = 1;
+pub const PT_DYNAMIC: u32 = 2;
+
+const DT_NULL: u64 = 0;
+const DT_NEEDED: u64 = 1;
+const DT_STRTAB: u64 = 5;
+const DT_SONAME: u64 = 14;
+
+/// Headers and dynamic linking information of an ELF shared object.
+#[derive(Clone, Debug, Default)]
+pub struct ElfFile {
+    pub is_64: bool,
+    pub big_endian: bool,
+    /// `e_machine`.
+    pub machine: u16,
+    pub program_headers: Vec<ProgramHeader>,
+    /// `DT_NEEDED` libraries, in order.
+    pub needed: Vec<String>,
+    pub soname: Option<String>,
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ProgramHeader {
+    pub p_type: u32,
+    pub offset: u64,
+    pub vaddr: u64,
+    pub filesz: u64,
+    pub align: u64,
+}
+
+impl ElfFile {
+    pub fn parse(buf: &[u8]) -> ExtResult<ElfFile> {
+        if !buf.starts_with(ELF_MAGIC) || buf.len() < 16 {
+            return Err(invalid("not an ELF file"));
+        }
+        let is_64 = buf[4] == ELFCLASS64;
+        let big_endian = buf[5] == ELFDATA2MSB;
+        let reader = |pos| {
+            let r = ByteReader::at(buf, pos);
+            if big_endian { r.big_endian() } else { r }
+        };
+        // 32-bit fields are widened, so both classes share one reader.
+        let word = |r: &mut ByteReader| -> ExtResult<u64> {
+            if is_64 { r.u64() } else { r.u32().map(u64::from) }
+        };
+
+        let mut r = reader(0x12);
+        let machine = r.u16()?;
+        r.skip(4)?;
+        let _entry = word(&mut r)?;
+        let phoff = word(&mut r)? as usize;
+        let _shoff = word(&mut r)?;
+        r.skip(4 + 2)?;
+        let phentsize = r.u16()? as usize;
+        let phnum = r.u16()? as usize;
+
+        let mut program_headers = vec![];
+        for i in 0..phnum {
+            let pos = i.checked_mul(phentsize)
+                .and_then(|offset| offset.checked_add(phoff))
+                .ok_or_else(|| invalid("program header offset overflows"))?;
+            let mut r = reader(pos);
+            let p_type = r.u32()?;
+            let header = if is_64 {
+                let _flags = r.u32()?;
+                let offset = r.u64()?;
+                let vaddr = r.u64()?;
+                let _paddr = r.u64()?;
+                let filesz = r.u64()?;
+                let _memsz = r.u64()?;
+                ProgramHeader { p_type, offset, vaddr, filesz, align: r.u64()? }
+            } else {
+                let offset = r.u32()?.into();
+                let vaddr = r.u32()?.into();
+                let _paddr = r.u32()?;
+                let filesz = r.u32()?.into();
+                let _memsz = r.u32()?;
+                let _flags = r.u32()?;
+                ProgramHeader { p_type, offset, vaddr, filesz, align: r.u32()?.into() }
+            };
+            program_headers.push(header);
+        }
+
+        let mut elf = ElfFile { is_64, big_endian, machine, program_headers, ..Default::default() };
+        let dynamic = match elf.program_headers.iter().find(|h| h.p_type == PT_DYNAMIC) {
+            Some(dynamic) => *dynamic,
+            None => return Ok(elf),
+        };
+
+        let mut entries = vec![];
+        let mut r = reader(dynamic.offset as usize);
+        let entry_size = if is_64 { 16 } else { 8 };
+        for _ in 0..dynamic.filesz / entry_size {
+            let tag = word(&mut r)?;
+            let value = word(&mut r)?;
+            if tag == DT_NULL {
+                break;
+            }
+            entries.push((tag, value));
+        }
+
+        let strtab = entries.iter()
+            .find(|(tag, _)| *tag == DT_STRTAB)
+            .and_then(|(_, vaddr)| elf.file_offset(*vaddr));
+        if let Some(strtab) = strtab {
+            let string = |offset: u64| c_string(buf, strtab.saturating_add(offset) as usize);
+            for (tag, value) in entries {
+                match tag {
+                    DT_NEEDED => elf.needed.push(string(value)),
+                    DT_SONAME => elf.soname = Some(string(value)),
+                    _ => {}
+                }
+            }
+        }
+
+        Ok(elf)
+    }
+
+    /// Maps a virtual address to a file offset through the `PT_LOAD` segments.
+    pub fn file_offset(&self, vaddr: u64) -> Option<u64> {
+        self.program_headers.iter()
+            .filter(|h| h.p_type == PT_LOAD)
+            .find(|h| vaddr >= h.vaddr && vaddr - h.vaddr < h.filesz)
+            .and_then(|h| (vaddr - h.vaddr).checked_add(h.offset))
+    }
+
+    pub fn machine_name(&self) -> String {
+        machine_name(self.machine)
+    }
+}
+
+/// The Android ABI whose libraries are built for an `e_machine`.
+pub fn machine_abi(machine: u16) -> Option<&'static str> {
+    match machine {
+        3 => Some("x86"),
+        40 => Some("armeabi-v7a"),
+        62 => Some("x86_64"),
+        183 => Some("arm64-v8a"),
+        243 => Some("riscv64"),
+        8 => Some("mips"),
+        _ => None,
+    }
+}
+
+pub fn machine_name(machine: u16) -> String {
+    match machine {
+        3 => "x86".into(),
+        8 => "MIPS".into(),
+        40 => "ARM".into(),
+        62 => "x86-64".into(),
+        183 => "AArch64".into(),
+        243 => "RISC-V".into(),
+        other => format!("machine {}", other),
+    }
+}
+
+fn c_string(buf: &[u8], offset: usize) -> String {
+    let bytes = buf.get(offset..).unwrap_or_default();
+    let end = bytes.iter().position(|b| *b == 0).unwrap_or(bytes.len());
+    String::from_utf8_lossy(&bytes[..end]).into_owned()
+}
+
+fn invalid(msg: &str) -> Error {
+    Error::InvalidElf(msg.to_string())
+}
+
+pub use native::{NativeLibraries, NativeLibrary};
+pub use page_size::{LibraryPageSize, PageSizeReport, PAGE_SIZE_16K};
@@ -0,0 +1,107 @@
+use core::fmt;
+use std::collections::{BTreeMap, BTreeSet};
+use super::{machine_abi, ElfFile};
+
+/// The `lib/<abi>/*.so` entries of an APK.
+#[derive(Clone, Debug, Default)]
+pub struct NativeLibraries {
+    pub libraries: Vec<NativeLibrary>,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct NativeLibrary {
+    /// Path inside the APK.
+    pub path: String,
+    pub abi: String,
+    pub name: String,
+    /// Uncompressed size.
+    pub size: u64,
+    /// Whether the entry is stored uncompressed, so it can be mapped in place.
+    pub stored: bool,
+    /// Offset of the entry's data in the APK.
+    pub data_offset: u64,
+    /// `None` if the entry is not a readable ELF file.
+    pub elf: Option<ElfFile>,
+}
+
+impl NativeLibrary {
+    /// The ABI and file name of a `lib/<abi>/<name>.so` path.
+    pub fn split_path(path: &str) -> Option<(&str, &str)> {
+        let rest = path.strip_prefix("lib/")?;
+        let (abi, name) = rest.split_once('/')?;
+        (name.ends_with(".so") && !name.contains('/')).then_some((abi, name))
+    }
+}
+
+impl NativeLibraries {
+    /// Libraries by ABI, sorted by name.
+    pub fn abis(&self) -> BTreeMap<&str, Vec<&NativeLibrary>> {
+        let mut abis: BTreeMap<&str, Vec<&NativeLibrary>> = BTreeMap::new();
+        for library in &self.libraries {
+            abis.entry(&library.abi).or_default().push(library);
+        }
+        for libraries in abis.values_mut() {
+            libraries.sort_by(|a, b| a.name.cmp(&b.name));
+        }
+        abis
+    }
+
+    /// ABIs missing libraries that others ship, which crash on devices that
+    /// pick that ABI, and libraries built for another ABI's machine.
+    pub fn inconsistencies(&self) -> Vec<String> {
+        let mut issues = vec![];
+        let abis = self.abis();
+        let all: BTreeSet<&str> = self.libraries.iter().map(|l| l.name.as_str()).collect();
+        for (abi, libraries) in &abis {
+            let names: BTreeSet<&str> = libraries.iter().map(|l| l.name.as_str()).collect();
+            let missing: Vec<&str> = all.difference(&names).copied().collect();
+            if !missing.is_empty() {
+                issues.push(format!("{} lacks {}", abi, missing.join(", ")));
+            }
+        }
+        for library in &self.libraries {
+            let elf_abi = library.elf.as_ref().and_then(|elf| machine_abi(elf.machine));
+            // armeabi and armeabi-v7a share a machine type.
+            let compatible = |a: &&str| *a == library.abi || (*a == "armeabi-v7a" && library.abi == "armeabi");
+            if let Some(elf_abi) = elf_abi.filter(|a| !compatible(a)) {
+                issues.push(format!("{} is built for {}", library.path, elf_abi));
+            }
+        }
+        issues
+    }
+}
+
+impl fmt::Display for NativeLibraries {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (abi, libraries) in self.abis() {
+            writeln!(f, "{}: {} librar{}", abi, libraries.len(), if libraries.len() == 1 { "y" } else { "ies" })?;
+            for library in libraries {
+                write!(f, "{}", library)?;
+            }
+        }
+        for issue in self.inconsistencies() {
+            writeln!(f, "ERROR: {}", issue)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for NativeLibrary {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let elf = match &self.elf {
+            Some(elf) => elf,
+            None => return writeln!(f, "  {} ({} bytes): not an ELF file", self.name, self.size),
+        };
+        writeln!(
+            f, "  {} ({} bytes): {} {}-bit",
+            self.name, self.size, elf.machine_name(), if elf.is_64 { 64 } else { 32 }
+        )?;
+        if let Some(soname) = &elf.soname {
+            writeln!(f, "    soname: {}", soname)?;
+        }
+        for needed in &elf.needed {
+            writeln!(f, "    needs: {}", needed)?;
+        }
+        Ok(())
+    }
+}
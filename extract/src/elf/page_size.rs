@@ -0,0 +1,113 @@
+use core::fmt;
+use super::{NativeLibraries, PT_LOAD};
+
+/// Google Play requires 64-bit libraries to support 16 KB memory pages.
+pub const PAGE_SIZE_16K: u64 = 16 * 1024;
+
+/// Whether an APK's native libraries load on 16 KB page devices.
+#[derive(Clone, Debug, Default)]
+pub struct PageSizeReport {
+    /// `android:extractNativeLibs` of the manifest, if set.
+    pub extract_native_libs: Option<bool>,
+    pub libraries: Vec<LibraryPageSize>,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct LibraryPageSize {
+    pub path: String,
+    /// `None` if the entry is not a readable ELF file.
+    pub is_64: Option<bool>,
+    pub stored: bool,
+    pub data_offset: u64,
+    /// Smallest `p_align` of the `PT_LOAD` segments.
+    pub load_align: Option<u64>,
+    pub errors: Vec<String>,
+    /// Problems that do not affect 16 KB page support.
+    pub warnings: Vec<String>,
+}
+
+impl LibraryPageSize {
+    pub fn compliant(&self) -> bool {
+        self.errors.is_empty()
+    }
+}
+
+impl PageSizeReport {
+    pub fn compliant(&self) -> bool {
+        self.libraries.iter().all(LibraryPageSize::compliant)
+    }
+}
+
+impl NativeLibraries {
+    /// Checks ZIP alignment of uncompressed libraries, `PT_LOAD` alignment of
+    /// 64-bit libraries, and that storage agrees with `extractNativeLibs`.
+    pub fn check_page_size(&self, extract_native_libs: Option<bool>) -> PageSizeReport {
+        let extract = extract_native_libs.unwrap_or(true);
+        let libraries = self.libraries.iter()
+            .map(|library| {
+                let mut errors = vec![];
+                let mut warnings = vec![];
+                let is_64 = library.elf.as_ref().map(|elf| elf.is_64);
+                let load_align = library.elf.as_ref().and_then(|elf| elf.program_headers.iter()
+                    .filter(|h| h.p_type == PT_LOAD)
+                    .map(|h| h.align)
+                    .min());
+
+                if library.stored && library.data_offset % PAGE_SIZE_16K != 0 {
+                    errors.push(format!("stored at offset {:#x}, not 16 KB aligned; run zipalign -P 16", library.data_offset));
+                }
+                if let Some(align) = load_align.filter(|align| is_64 == Some(true) && align % PAGE_SIZE_16K != 0) {
+                    errors.push(format!("PT_LOAD segments are aligned to {:#x}, not 16 KB; link with -z max-page-size=16384", align));
+                }
+                if !extract && !library.stored {
+                    errors.push("compressed, but extractNativeLibs=\"false\" needs it stored".to_string());
+                }
+                if extract && library.stored && extract_native_libs.is_some() {
+                    warnings.push("stored uncompressed, but extractNativeLibs=\"true\" extracts it anyway".to_string());
+                }
+
+                LibraryPageSize {
+                    path: library.path.clone(),
+                    is_64,
+                    stored: library.stored,
+                    data_offset: library.data_offset,
+                    load_align,
+                    errors,
+                    warnings,
+                }
+            })
+            .collect();
+
+        PageSizeReport { extract_native_libs, libraries }
+    }
+}
+
+impl fmt::Display for PageSizeReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.extract_native_libs {
+            Some(extract) => writeln!(f, "extractNativeLibs: {}", extract)?,
+            None => writeln!(f, "extractNativeLibs: not set")?,
+        }
+        for library in &self.libraries {
+            writeln!(
+                f, "{}: {}, {}, load alignment {}: {}",
+                library.path,
+                match library.is_64 {
+                    Some(true) => "64-bit",
+                    Some(false) => "32-bit",
+                    None => "not ELF",
+                },
+                if library.stored { format!("stored at {:#x}", library.data_offset) } else { "compressed".to_string() },
+                library.load_align.map_or("unknown".to_string(), |a| format!("{:#x}", a)),
+                if library.compliant() { "16 KB compatible" } else { "NOT 16 KB compatible" },
+            )?;
+            for err in &library.errors {
+                writeln!(f, "ERROR: {}: {}", library.path, err)?;
+            }
+            for warning in &library.warnings {
+                writeln!(f, "WARNING: {}: {}", library.path, warning)?;
+            }
+        }
+        writeln!(f, "16 KB page size compliant: {}", self.compliant())
+    }
+}
@@ -6,6 +6,8 @@ use thiserror::Error;
 use plist::Error as PlistError;
 use zip::result::ZipError;
 
+use crate::resource::ResourceId;
+
 pub type ExtResult<T> = std::result::Result<T, Error>;
 
 #[derive(Debug, Error)]
@@ -25,4 +27,30 @@ pub enum Error {
     InvalidFile,
     #[error("The file does not correspond to this type")]
     IncorrectLoader,
+    #[error("Unexpected end of data")]
+    UnexpectedEof,
+    #[error("Invalid resource table: {}", .0)]
+    InvalidResourceTable(String),
+    #[error("Resource {} not found", .0)]
+    ResourceNotFound(ResourceId),
+    #[error("Resource {} has a reference cycle", .0)]
+    ResourceCycle(ResourceId),
+    #[error("Invalid device configuration qualifier: {}", .0)]
+    InvalidDeviceConfig(String),
+    #[error("Invalid channel information: {}", .0)]
+    InvalidChannel(String),
+    #[error("Invalid Mach-O file: {}", .0)]
+    InvalidMachO(String),
+    #[error("Invalid ELF file: {}", .0)]
+    InvalidElf(String),
+    #[error("Invalid DEX file: {}", .0)]
+    InvalidDex(String),
+    #[error("Invalid code signature: {}", .0)]
+    InvalidCodeSignature(String),
+    #[error("Invalid DER data: {}", .0)]
+    InvalidDer(String),
+    #[error("Invalid APK signing block: {}", .0)]
+    InvalidSigningBlock(String),
+    #[error("Invalid policy: {}", .0)]
+    InvalidPolicy(String),
 }
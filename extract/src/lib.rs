@@ -1,7 +1,9 @@
 use std::{
-    fs::File,
-    io::{BufReader, Read},
+    collections::BTreeMap,
+    fs::{self, File},
+    io::{BufReader, BufWriter, Read, Seek},
     path::PathBuf,
+    time::SystemTime,
     ffi::OsStr
 };
 use regex::Regex;
@@ -11,20 +13,56 @@ use error::{Error, ExtResult};
 use manifest::{
     Manifest,
     IPA_EXT, IpaManifest,
-    APK_EXT, ApkManifest,
+    APK_EXT, ApkManifest, ApkDeclarations, NetworkSecurityConfig,
+    PRIVACY_MANIFEST_NAME, PrivacyReport, PrivacyManifest, UsageDescription, parse_strings,
 };
+use bundle::{BundleComponent, BundleInventory, ComponentKind};
+use dex::{DexFile, DexFiles};
+use elf::{ElfFile, NativeLibraries, NativeLibrary, PageSizeReport};
+use lint::{LintReport, ANDROID_RULES, IOS_RULES, lint_android_manifest, lint_network_security_config, lint_info_plist, lint_signing, lint_executable};
+use codesign::{IpaSigningInfo, ProvisioningProfile, ProfileCheck, CodeSignature};
+use macho::{EncryptionInfo, MachO, MachOInfo, is_macho};
+use policy::{PackageFacts, Policy, PolicyReport};
+use sdk::{PackageContents, SdkReport, SDK_SIGNATURES};
+use resource::{ResourceId, ResourceTable, DeviceConfig};
+use signing::{SigningInfo, V4Signature, Verification, read_v1_signers, read_block_signers, read_channel, verify_apk};
 
+pub mod bundle;
+pub mod codesign;
+pub mod dex;
+pub mod elf;
 pub mod error;
+pub mod lint;
+pub mod macho;
 pub mod manifest;
+pub mod policy;
+pub mod resource;
+pub mod sdk;
+pub mod signing;
+mod bytes;
+mod date;
+mod der;
+mod pkcs7;
 
 pub const APK_META_PATH: &str = "AndroidManifest.xml";
 pub const APK_ARSC_PATH: &str = "resources.arsc";
 
 lazy_static! {
     static ref IPA_META_PATH: Regex = Regex::new(r"Payload/[^/]+\.app/Info\.plist").unwrap();
+    static ref IPA_APP_INFO_PATH: Regex = Regex::new(r"^(Payload/[^/]+\.app/)Info\.plist$").unwrap();
+    static ref IPA_PROFILE_PATH: Regex = Regex::new(r"^Payload/[^/]+\.app/embedded\.mobileprovision$").unwrap();
 }
 
 pub fn get_loaders(path: &PathBuf) -> ExtResult<Manifest> {
+    load(path, None)
+}
+
+/// Like [`get_loaders`], resolving APK resources (label, icon, ...) as `device` would see them.
+pub fn get_loaders_for(path: &PathBuf, device: &DeviceConfig) -> ExtResult<Manifest> {
+    load(path, Some(device))
+}
+
+fn load(path: &PathBuf, device: Option<&DeviceConfig>) -> ExtResult<Manifest> {
     let ext = path
         .extension()
         .and_then(OsStr::to_str);
@@ -32,7 +70,7 @@ pub fn get_loaders(path: &PathBuf) -> ExtResult<Manifest> {
     match ext {
         Some(str) => {
             if str == IPA_EXT || str == APK_EXT {
-                get_from_path(path, str)
+                load_from_path(path, str, device)
             } else {
                 Err(Error::InvalidFile)
             }
@@ -41,7 +79,496 @@ pub fn get_loaders(path: &PathBuf) -> ExtResult<Manifest> {
     }
 }
 
+pub fn get_resource_table(path: &PathBuf) -> ExtResult<ResourceTable> {
+    let file = File::open(path)?;
+    let reader = BufReader::new(file);
+    let mut archive = zip::ZipArchive::new(reader)?;
+
+    let mut buf: Vec<u8> = Vec::new();
+    archive.by_name(APK_ARSC_PATH)?
+        .read_to_end(&mut buf)?;
+
+    ResourceTable::parse(&buf)
+}
+
+pub fn get_signing_info(path: &PathBuf) -> ExtResult<SigningInfo> {
+    let file = File::open(path)?;
+    let reader = BufReader::new(file);
+    let mut archive = zip::ZipArchive::new(reader)?;
+    let v1 = read_v1_signers(&mut archive)?;
+
+    let mut info = read_block_signers(&mut archive.into_inner())?;
+    info.v1 = v1;
+
+    let idsig = PathBuf::from(format!("{}.idsig", path.display()));
+    if idsig.is_file() {
+        info.v4 = Some(V4Signature::parse(&fs::read(idsig)?)?);
+    }
+
+    Ok(info)
+}
+
+pub fn verify_signatures(path: &PathBuf) -> ExtResult<Verification> {
+    let file = File::open(path)?;
+    verify_apk(BufReader::new(file))
+}
+
+/// Reads the provisioning profile embedded in the app bundle of an IPA, and
+/// the code signature of its main executable.
+pub fn get_ipa_signing_info(path: &PathBuf) -> ExtResult<IpaSigningInfo> {
+    let file = File::open(path)?;
+    let reader = BufReader::new(file);
+    let mut archive = zip::ZipArchive::new(reader)?;
+    let mut info = IpaSigningInfo::default();
+
+    let profile = archive.file_names()
+        .find(|n| IPA_PROFILE_PATH.is_match(n))
+        .map(ToString::to_string);
+    if let Some(name) = profile {
+        let mut buf: Vec<u8> = Vec::new();
+        archive.by_name(&name)?
+            .read_to_end(&mut buf)?;
+        info.profile = Some(ProvisioningProfile::from_buffer(&buf)?);
+    }
+
+    let (bundle, plist) = match read_ipa_info_plist(&mut archive)? {
+        Some(info) => info,
+        None => return Ok(info),
+    };
+    info.bundle_id = plist.get("CFBundleIdentifier")
+        .and_then(plist::Value::as_string)
+        .map(ToString::to_string);
+
+    if let Some(executable) = main_executable(&bundle, &plist) {
+        let mut buf: Vec<u8> = Vec::new();
+        archive.by_name(&executable)?
+            .read_to_end(&mut buf)?;
+        for slice in MachO::parse_all(&buf)? {
+            if let Some(signature) = CodeSignature::from_macho(&slice)? {
+                info.code_signatures.push(signature);
+            }
+        }
+    }
+
+    Ok(info)
+}
+
+/// Architectures, deployment targets, hardening flags and linked libraries of
+/// an IPA's main executable.
+pub fn get_ipa_executable_info(path: &PathBuf) -> ExtResult<Option<MachOInfo>> {
+    let file = File::open(path)?;
+    let reader = BufReader::new(file);
+    let mut archive = zip::ZipArchive::new(reader)?;
+
+    let executable = match read_ipa_info_plist(&mut archive)? {
+        Some((bundle, plist)) => main_executable(&bundle, &plist),
+        None => None,
+    };
+    let executable = match executable {
+        Some(executable) => executable,
+        None => return Ok(None),
+    };
+
+    let mut buf: Vec<u8> = Vec::new();
+    archive.by_name(&executable)?
+        .read_to_end(&mut buf)?;
+    MachOInfo::parse(&executable, &buf).map(Some)
+}
+
+/// FairPlay encryption state of every Mach-O file in an IPA's app bundle,
+/// including frameworks, dylibs and extensions. Files with a Mach-O magic
+/// that fail to parse are left out.
+pub fn get_ipa_encryption_info(path: &PathBuf) -> ExtResult<Vec<EncryptionInfo>> {
+    let file = File::open(path)?;
+    let reader = BufReader::new(file);
+    let mut archive = zip::ZipArchive::new(reader)?;
+
+    let bundle = match ipa_bundle_dir(&archive) {
+        Some(bundle) => bundle,
+        None => return Ok(vec![]),
+    };
+    let names: Vec<String> = archive.file_names()
+        .filter(|n| n.starts_with(&bundle) && !n.ends_with('/'))
+        .map(ToString::to_string)
+        .collect();
+
+    let mut infos = vec![];
+    for name in names {
+        let mut file = archive.by_name(&name)?;
+        let mut magic: Vec<u8> = Vec::new();
+        (&mut file).take(8).read_to_end(&mut magic)?;
+        if !is_macho(&magic) {
+            continue;
+        }
+        let mut buf = magic;
+        file.read_to_end(&mut buf)?;
+        if let Ok(info) = EncryptionInfo::parse(&name, &buf) {
+            infos.push(info);
+        }
+    }
+    infos.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(infos)
+}
+
+/// Lists the frameworks, dylibs, app extensions, watch apps and App Clips
+/// embedded in an IPA's app bundle.
+pub fn get_ipa_bundle_inventory(path: &PathBuf) -> ExtResult<BundleInventory> {
+    let file = File::open(path)?;
+    let reader = BufReader::new(file);
+    let mut archive = zip::ZipArchive::new(reader)?;
+    read_ipa_bundle_inventory(&mut archive)
+}
+
+fn read_ipa_bundle_inventory<R: Read + Seek>(archive: &mut zip::ZipArchive<R>) -> ExtResult<BundleInventory> {
+    let mut inventory = BundleInventory::default();
+
+    let (bundle, plist) = match read_ipa_info_plist(archive)? {
+        Some(info) => info,
+        None => return Ok(inventory),
+    };
+    inventory.version = plist.get("CFBundleShortVersionString")
+        .and_then(plist::Value::as_string)
+        .map(ToString::to_string);
+    inventory.build_number = plist.get("CFBundleVersion")
+        .and_then(plist::Value::as_string)
+        .map(ToString::to_string);
+
+    let mut components: BTreeMap<String, BundleComponent> = BTreeMap::new();
+    for i in 0..archive.len() {
+        let file = archive.by_index(i)?;
+        let relative = match file.name().strip_prefix(&bundle) {
+            Some(relative) => relative,
+            None => continue,
+        };
+        if let Some((root, kind)) = BundleComponent::root_of(relative) {
+            components.entry(root.clone())
+                .or_insert_with(|| BundleComponent::new(root, kind))
+                .size += file.size();
+        }
+    }
+
+    for component in components.values_mut() {
+        let name = format!("{}{}/Info.plist", bundle, component.path);
+        let mut buf: Vec<u8> = Vec::new();
+        match archive.by_name(&name) {
+            Ok(mut file) => file.read_to_end(&mut buf)?,
+            Err(zip::result::ZipError::FileNotFound) => continue,
+            Err(err) => return Err(err.into()),
+        };
+        component.read_info_plist(&plist::from_bytes(&buf)?);
+    }
+    inventory.components = components.into_values().collect();
+
+    Ok(inventory)
+}
+
+/// Path of the main executable, as named by `CFBundleExecutable`.
+fn main_executable(bundle: &str, plist: &plist::Dictionary) -> Option<String> {
+    plist.get("CFBundleExecutable")
+        .and_then(plist::Value::as_string)
+        .map(|executable| format!("{}{}", bundle, executable))
+}
+
+/// The app bundle directory of an IPA, with a trailing slash, and its Info.plist.
+fn read_ipa_info_plist<R: Read + Seek>(archive: &mut zip::ZipArchive<R>) -> ExtResult<Option<(String, plist::Dictionary)>> {
+    let bundle = match ipa_bundle_dir(archive) {
+        Some(bundle) => bundle,
+        None => return Ok(None),
+    };
+
+    let mut buf: Vec<u8> = Vec::new();
+    archive.by_name(&format!("{}Info.plist", bundle))?
+        .read_to_end(&mut buf)?;
+    let plist: plist::Dictionary = plist::from_bytes(&buf)?;
+
+    Ok(Some((bundle, plist)))
+}
+
+/// The app bundle directory of an IPA, with a trailing slash.
+fn ipa_bundle_dir<R: Read + Seek>(archive: &zip::ZipArchive<R>) -> Option<String> {
+    archive.file_names()
+        .find_map(|n| IPA_APP_INFO_PATH.captures(n).map(|c| c[1].to_string()))
+}
+
+/// Checks an IPA's code signature against its embedded provisioning profile.
+pub fn check_ipa_profile(path: &PathBuf) -> ExtResult<ProfileCheck> {
+    let info = get_ipa_signing_info(path)?;
+    Ok(info.check(date::unix_timestamp(SystemTime::now())))
+}
+
+/// Runs the security lint rules over an APK's manifest, or an IPA's
+/// Info.plist, code signature and main executable.
+pub fn lint(path: &PathBuf) -> ExtResult<LintReport> {
+    let file = File::open(path)?;
+    let reader = BufReader::new(file);
+    let mut archive = zip::ZipArchive::new(reader)?;
+    let mut report = LintReport::default();
+
+    if path.extension().is_some_and(|ext| ext == IPA_EXT) {
+        report.rules = IOS_RULES;
+        if let Some((_, plist)) = read_ipa_info_plist(&mut archive)? {
+            report.findings.extend(lint_info_plist(&plist));
+        }
+        report.findings.extend(lint_signing(&get_ipa_signing_info(path)?));
+        if let Some(executable) = get_ipa_executable_info(path)? {
+            report.findings.extend(lint_executable(&executable));
+        }
+    } else {
+        let mut buf: Vec<u8> = Vec::new();
+        archive.by_name(APK_META_PATH)?
+            .read_to_end(&mut buf)?;
+        let xml = manifest::axml::extract_xml(buf)?;
+        let mut arsc_buf: Vec<u8> = Vec::new();
+        let table = match archive.by_name(APK_ARSC_PATH) {
+            Ok(mut file) => file.read_to_end(&mut arsc_buf).ok().and_then(|_| ResourceTable::parse(&arsc_buf).ok()),
+            Err(_) => None,
+        };
+        let resolve = |value: &str| table.as_ref()
+            .and_then(|table| manifest::find_resource_by_id(table, value.to_string(), None));
+        report.rules = ANDROID_RULES;
+        report.findings = lint_android_manifest(&xml, resolve)?;
+        if let Some(config) = read_network_security_config(&mut archive, &xml)? {
+            let today = date::format_timestamp(date::unix_timestamp(SystemTime::now()));
+            report.findings.extend(lint_network_security_config(&config, &today[..10]));
+        }
+    }
+
+    report.sort();
+    Ok(report)
+}
+
+/// Lists an APK's native libraries per ABI, with their ELF dependencies.
+pub fn get_native_libraries(path: &PathBuf) -> ExtResult<NativeLibraries> {
+    let file = File::open(path)?;
+    let reader = BufReader::new(file);
+    let mut archive = zip::ZipArchive::new(reader)?;
+    read_native_libraries(&mut archive)
+}
+
+/// Detects third-party SDKs from an APK's code, native libraries and Maven
+/// version files, or an IPA's frameworks and Info.plist.
+pub fn detect_sdks(path: &PathBuf) -> ExtResult<SdkReport> {
+    let file = File::open(path)?;
+    let reader = BufReader::new(file);
+    let mut archive = zip::ZipArchive::new(reader)?;
+
+    if path.extension().is_some_and(|ext| ext == IPA_EXT) {
+        read_ipa_sdks(&mut archive)
+    } else {
+        read_apk_sdks(&mut archive)
+    }
+}
+
+/// Detection is best-effort, so DEX and version files that fail to parse are skipped.
+fn read_apk_sdks<R: Read + Seek>(archive: &mut zip::ZipArchive<R>) -> ExtResult<SdkReport> {
+    let mut contents = PackageContents {
+        native_libraries: archive.file_names()
+            .filter_map(NativeLibrary::split_path)
+            .map(|(_, name)| name.to_string())
+            .collect(),
+        ..Default::default()
+    };
+
+    let names: Vec<String> = archive.file_names().map(ToString::to_string).collect();
+    for name in names {
+        let is_dex = DexFiles::index_of(&name).is_some();
+        let is_version = name.starts_with("META-INF/") && name.ends_with(".version");
+        if !is_dex && !is_version {
+            continue;
+        }
+        let mut buf: Vec<u8> = Vec::new();
+        archive.by_name(&name)?
+            .read_to_end(&mut buf)?;
+        if is_dex {
+            if let Ok(dex) = DexFile::parse(&name, &buf) {
+                contents.dex_packages.extend(dex.package_methods.into_keys());
+            }
+            continue;
+        }
+        let version = match String::from_utf8(buf) {
+            Ok(version) => version,
+            Err(_) => continue,
+        };
+        let artifact = &name["META-INF/".len()..name.len() - ".version".len()];
+        contents.maven_versions.insert(artifact.to_string(), version.trim().to_string());
+    }
+
+    Ok(contents.detect(SDK_SIGNATURES))
+}
+
+fn read_ipa_sdks<R: Read + Seek>(archive: &mut zip::ZipArchive<R>) -> ExtResult<SdkReport> {
+    let mut contents = PackageContents::default();
+    if let Some((_, plist)) = read_ipa_info_plist(archive)? {
+        contents.plist_keys = plist.keys().cloned().collect();
+    }
+    contents.frameworks = read_ipa_bundle_inventory(archive)?.components.into_iter()
+        .filter(|c| c.kind == ComponentKind::Framework)
+        .filter_map(|c| {
+            let name = c.path.strip_prefix("Frameworks/")?.strip_suffix(".framework")?;
+            Some((name.to_string(), c.version))
+        })
+        .collect();
+
+    Ok(contents.detect(SDK_SIGNATURES))
+}
+
+pub fn get_dex_files(path: &PathBuf) -> ExtResult<DexFiles> {
+    let file = File::open(path)?;
+    let reader = BufReader::new(file);
+    let mut archive = zip::ZipArchive::new(reader)?;
+    read_dex_files(&mut archive)
+}
+
+fn read_dex_files<R: Read + Seek>(archive: &mut zip::ZipArchive<R>) -> ExtResult<DexFiles> {
+    let mut names: Vec<(u32, String)> = archive.file_names()
+        .filter_map(|name| DexFiles::index_of(name).map(|index| (index, name.to_string())))
+        .collect();
+    names.sort();
+
+    let mut dex = DexFiles::default();
+    for (_, name) in names {
+        let mut buf: Vec<u8> = Vec::new();
+        archive.by_name(&name)?
+            .read_to_end(&mut buf)?;
+        dex.files.push(DexFile::parse(&name, &buf)?);
+    }
+    Ok(dex)
+}
+
+/// Checks that an APK's native libraries support 16 KB memory pages.
+pub fn check_page_size(path: &PathBuf) -> ExtResult<PageSizeReport> {
+    let file = File::open(path)?;
+    let reader = BufReader::new(file);
+    let mut archive = zip::ZipArchive::new(reader)?;
+
+    let mut buf: Vec<u8> = Vec::new();
+    archive.by_name(APK_META_PATH)?
+        .read_to_end(&mut buf)?;
+    let declarations = ApkDeclarations::from_xml(&manifest::axml::extract_xml(buf)?)?;
+    Ok(read_native_libraries(&mut archive)?.check_page_size(declarations.extract_native_libs))
+}
+
+fn read_native_libraries<R: Read + Seek>(archive: &mut zip::ZipArchive<R>) -> ExtResult<NativeLibraries> {
+    let mut libraries = NativeLibraries::default();
+    for i in 0..archive.len() {
+        let mut file = archive.by_index(i)?;
+        let (abi, name) = match NativeLibrary::split_path(file.name()) {
+            Some((abi, name)) => (abi.to_string(), name.to_string()),
+            None => continue,
+        };
+        // A corrupt entry is still listed, only without its ELF details.
+        let mut buf: Vec<u8> = Vec::new();
+        let read = file.read_to_end(&mut buf);
+        libraries.libraries.push(NativeLibrary {
+            path: file.name().to_string(),
+            abi,
+            name,
+            size: file.size(),
+            stored: file.compression() == zip::CompressionMethod::Stored,
+            data_offset: file.data_start(),
+            elf: read.ok().and_then(|_| ElfFile::parse(&buf).ok()),
+        });
+    }
+    Ok(libraries)
+}
+
+/// Reads the network security config an APK's manifest points to, if any.
+pub fn get_network_security_config(path: &PathBuf) -> ExtResult<Option<NetworkSecurityConfig>> {
+    let file = File::open(path)?;
+    let reader = BufReader::new(file);
+    let mut archive = zip::ZipArchive::new(reader)?;
+
+    let mut buf: Vec<u8> = Vec::new();
+    archive.by_name(APK_META_PATH)?
+        .read_to_end(&mut buf)?;
+    read_network_security_config(&mut archive, &manifest::axml::extract_xml(buf)?)
+}
+
+fn read_network_security_config<R: Read + Seek>(archive: &mut zip::ZipArchive<R>, manifest_xml: &str) -> ExtResult<Option<NetworkSecurityConfig>> {
+    let reference = match NetworkSecurityConfig::reference_in(manifest_xml)? {
+        Some(reference) => reference,
+        None => return Ok(None),
+    };
+
+    let mut arsc_buf: Vec<u8> = Vec::new();
+    archive.by_name(APK_ARSC_PATH)?
+        .read_to_end(&mut arsc_buf)?;
+    let table = ResourceTable::parse(&arsc_buf)?;
+    let resolve = |value: &str| manifest::find_resource_by_id(&table, value.to_string(), None);
+    let path = match resolve(&reference) {
+        Some(path) => path,
+        None => match reference.strip_prefix(manifest::RESOURCE_VALUE_TYPE).and_then(|id| id.parse().ok()) {
+            Some(id) => return Err(Error::ResourceNotFound(ResourceId::from_u32(id))),
+            None => reference,
+        },
+    };
+
+    let mut buf: Vec<u8> = Vec::new();
+    archive.by_name(&path)?
+        .read_to_end(&mut buf)?;
+    NetworkSecurityConfig::parse(&path, &manifest::axml::extract_xml(buf)?, resolve).map(Some)
+}
+
+/// Evaluates a package against the rules of its product in `policy`.
+pub fn evaluate_policy(path: &PathBuf, policy: &Policy) -> ExtResult<PolicyReport> {
+    let manifest = get_loaders(path)?;
+    let mut facts = PackageFacts {
+        bundle_id: manifest.bundle_id,
+        version: manifest.version,
+        build_number: manifest.build_number,
+        size: fs::metadata(path)?.len(),
+        ..Default::default()
+    };
+
+    if path.extension().is_some_and(|ext| ext == IPA_EXT) {
+        let signing = get_ipa_signing_info(path)?;
+        facts.debuggable = signing.code_signatures.iter()
+            .any(|s| s.entitlements.get("get-task-allow").and_then(plist::Value::as_boolean) == Some(true));
+        if let Some(profile) = signing.profile {
+            facts.provisioning = Some(profile.distribution.to_string());
+            facts.signer_sha256 = profile.certificates.into_iter().map(|c| c.sha256).collect();
+        }
+    } else {
+        let mut archive = zip::ZipArchive::new(BufReader::new(File::open(path)?))?;
+        let mut buf: Vec<u8> = Vec::new();
+        archive.by_name(APK_META_PATH)?
+            .read_to_end(&mut buf)?;
+        let declarations = ApkDeclarations::from_xml(&manifest::axml::extract_xml(buf)?)?;
+        facts.permissions = Some(declarations.permissions);
+        facts.min_sdk = declarations.min_sdk;
+        facts.target_sdk = declarations.target_sdk;
+        facts.debuggable = declarations.debuggable;
+
+        let signing = get_signing_info(path)?;
+        let signers = signing.v1.iter().map(|s| &s.certificates)
+            .chain(signing.v2.iter().chain(&signing.v3).chain(&signing.v31).map(|s| &s.certificates));
+        facts.signer_sha256 = signers
+            .filter_map(|certificates| certificates.first())
+            .map(|c| c.sha256.clone())
+            .collect();
+    }
+
+    policy.evaluate(&facts)
+}
+
+/// Writes a copy of the APK at `path` to `output` carrying a Walle channel and
+/// extra key/values, keeping its v2/v3 signatures valid. `output` must differ from `path`.
+/// A v4 `<path>.idsig` does not cover `output`; the APK has to be re-signed for one.
+pub fn write_channel(path: &PathBuf, output: &PathBuf, channel: &str, extras: &BTreeMap<String, String>) -> ExtResult<()> {
+    // Creating `output` truncates it, which would destroy the input before it is read.
+    if fs::canonicalize(output).ok() == Some(fs::canonicalize(path)?) {
+        return Err(Error::InvalidChannel("the output file is the input APK".to_string()));
+    }
+    let mut reader = BufReader::new(File::open(path)?);
+    let mut writer = BufWriter::new(File::create(output)?);
+    signing::write_channel(&mut reader, &mut writer, channel, extras)
+}
+
 pub fn get_from_path (path: &PathBuf, ext: &str) -> ExtResult<Manifest> {
+    load_from_path(path, ext, None)
+}
+
+fn load_from_path (path: &PathBuf, ext: &str, device: Option<&DeviceConfig>) -> ExtResult<Manifest> {
     let file = File::open(path)?;
     let mut name = String::new();
     let mut manifest = Manifest::default();
@@ -69,24 +596,67 @@ pub fn get_from_path (path: &PathBuf, ext: &str) -> ExtResult<Manifest> {
         .read_to_end(&mut buf)?;
 
     if ext == APK_EXT {        
-        manifest = ApkManifest::from_buffer(buf, arsc_buf)?;
+        manifest = ApkManifest::from_buffer_for(buf, arsc_buf, device)?;
         let mut icon_buf: Vec<u8> = Vec::new();
         archive.by_name(&manifest.icon)?
             .read_to_end(&mut icon_buf)?;
         manifest.icon = base64::encode(icon_buf);
+        // The channel is optional metadata; only the `channel` command reports its errors.
+        manifest.channel = read_channel(&mut archive.into_inner()).ok().flatten();
     } else {
+        let bundle = name.strip_suffix("Info.plist").unwrap_or_default().to_string();
+        let info_plist: plist::Dictionary = plist::from_bytes(&buf)?;
         manifest = IpaManifest::from_buffer(buf)?;
         let mut icon_buf: Vec<u8> = Vec::new();
-        let names = archive.file_names()
+        let name = archive.file_names()
+                        .find(|f| f.contains(&manifest.icon))
                         .map(ToString::to_string)
-                        .filter(|f| f.contains(&manifest.icon))
-                        .collect::<Vec<String>>();
-        let name = names.first().unwrap();
-        println!("----{:?}", names);
+                        .ok_or(zip::result::ZipError::FileNotFound)?;
         archive.by_name(&name)?
             .read_to_end(&mut icon_buf)?;
         manifest.icon = base64::encode(icon_buf);
+
+        manifest.privacy = Some(read_ipa_privacy(&mut archive, &bundle, &info_plist)?);
     }
 
     Ok(manifest)
-}
\ No newline at end of file
+}
+
+/// Usage descriptions of the main Info.plist with their translations, and every
+/// privacy manifest in the app bundle.
+fn read_ipa_privacy<R: Read + Seek>(archive: &mut zip::ZipArchive<R>, bundle: &str, info_plist: &plist::Dictionary) -> ExtResult<PrivacyReport> {
+    let mut report = PrivacyReport {
+        usage_descriptions: UsageDescription::from_info_plist(info_plist),
+        ..Default::default()
+    };
+
+    let names: Vec<String> = archive.file_names()
+        .filter(|n| n.starts_with(bundle))
+        .map(ToString::to_string)
+        .collect();
+    for name in names {
+        let relative = &name[bundle.len()..];
+        // Only the app's own translations; frameworks ship their own InfoPlist.strings.
+        let lang = relative.strip_suffix(".lproj/InfoPlist.strings")
+            .filter(|lang| !lang.contains('/'));
+        let is_manifest = relative.rsplit('/').next() == Some(PRIVACY_MANIFEST_NAME);
+        if lang.is_none() && !is_manifest {
+            continue;
+        }
+
+        let mut buf: Vec<u8> = Vec::new();
+        archive.by_name(&name)?
+            .read_to_end(&mut buf)?;
+        // A malformed file in some framework should not fail loading the app.
+        match lang {
+            Some(lang) => if let Ok(strings) = parse_strings(&buf) {
+                report.add_localization(lang, &strings);
+            },
+            None => if let Ok(manifest) = PrivacyManifest::from_buffer(&name, &buf) {
+                report.manifests.push(manifest);
+            },
+        }
+    }
+
+    Ok(report)
+}
@@ -0,0 +1,241 @@
+use std::collections::HashMap;
+use xml::{EventReader, reader::XmlEvent};
+use crate::{
+    error::ExtResult,
+    manifest::{axml, NetworkSecurityConfig, RESOURCE_VALUE_TYPE},
+};
+use super::{Finding, Rule, Severity};
+
+const DEBUGGABLE: Rule = Rule { id: "AND001", severity: Severity::High, title: "Application is debuggable" };
+const ALLOW_BACKUP: Rule = Rule { id: "AND002", severity: Severity::Medium, title: "Application data can be backed up" };
+const CLEARTEXT: Rule = Rule { id: "AND003", severity: Severity::Medium, title: "Cleartext traffic is allowed" };
+const EXPORTED: Rule = Rule { id: "AND004", severity: Severity::Medium, title: "Exported component is not protected by a permission" };
+const GRANT_URI: Rule = Rule { id: "AND005", severity: Severity::High, title: "Exported provider grants URI permissions" };
+const TASK_AFFINITY: Rule = Rule { id: "AND006", severity: Severity::Medium, title: "Task affinity allows task hijacking" };
+const MISSING_EXPORTED: Rule = Rule { id: "AND007", severity: Severity::High, title: "Component with an intent filter lacks android:exported" };
+const WEAK_PERMISSION: Rule = Rule { id: "AND008", severity: Severity::Medium, title: "Custom permission has a weak protection level" };
+const NSC_CLEARTEXT: Rule = Rule { id: "AND009", severity: Severity::Medium, title: "Network security config permits cleartext traffic" };
+const NSC_USER_CA: Rule = Rule { id: "AND010", severity: Severity::Medium, title: "Network security config trusts user-installed CAs" };
+const NSC_PINS_EXPIRED: Rule = Rule { id: "AND011", severity: Severity::Low, title: "Certificate pins have expired" };
+
+pub const ANDROID_RULES: &[Rule] = &[
+    DEBUGGABLE, ALLOW_BACKUP, CLEARTEXT, EXPORTED, GRANT_URI, TASK_AFFINITY, MISSING_EXPORTED, WEAK_PERMISSION,
+    NSC_CLEARTEXT, NSC_USER_CA, NSC_PINS_EXPIRED,
+];
+
+const COMPONENT_TAGS: &[&str] = &["activity", "activity-alias", "service", "receiver", "provider"];
+
+/// Android 12 (API 31) refuses to install apps whose components have intent
+/// filters but no explicit `android:exported`.
+const EXPLICIT_EXPORTED_SDK: u32 = 31;
+/// Cleartext traffic is disabled by default from Android 9 (API 28).
+const CLEARTEXT_DEFAULT_OFF_SDK: u32 = 28;
+/// Providers are exported by default below API 17.
+const PROVIDER_DEFAULT_PRIVATE_SDK: u32 = 17;
+
+#[derive(Default)]
+struct Component {
+    tag: String,
+    attrs: HashMap<String, String>,
+    has_intent_filter: bool,
+    /// Whether an intent filter makes this the launcher activity.
+    launcher: bool,
+}
+
+impl Component {
+    fn element(&self) -> String {
+        match self.attrs.get("name") {
+            Some(name) => format!("{} {}", self.tag, name),
+            None => self.tag.clone(),
+        }
+    }
+}
+
+/// Runs the Android rules over a decoded AndroidManifest.xml, with `resolve`
+/// turning resource references such as `@bool/exported` into values. Rules
+/// skip attributes whose reference does not resolve.
+pub fn lint_android_manifest(xml: &str, resolve: impl Fn(&str) -> Option<String>) -> ExtResult<Vec<Finding>> {
+    let mut package = String::new();
+    let mut target_sdk: Option<u32> = None;
+    let mut application: HashMap<String, String> = HashMap::new();
+    let mut permissions: Vec<HashMap<String, String>> = vec![];
+    let mut components: Vec<Component> = vec![];
+    let mut current: Option<Component> = None;
+    let mut filter: (bool, bool) = (false, false);
+
+    for e in EventReader::from_str(xml) {
+        match e {
+            Ok(XmlEvent::StartElement { name, attributes, .. }) => {
+                let attrs: HashMap<String, String> = attributes.into_iter()
+                    .map(|a| {
+                        let value = if a.value.starts_with(RESOURCE_VALUE_TYPE) {
+                            resolve(&a.value).unwrap_or(a.value)
+                        } else {
+                            a.value
+                        };
+                        (a.name.local_name, value)
+                    })
+                    .collect();
+                match name.local_name.as_str() {
+                    "manifest" => package = attrs.get("package").cloned().unwrap_or_default(),
+                    "uses-sdk" => target_sdk = attrs.get("targetSdkVersion")
+                        .or_else(|| attrs.get("minSdkVersion"))
+                        .and_then(|v| axml::int_value(v)),
+                    "application" => application = attrs,
+                    "permission" => permissions.push(attrs),
+                    "intent-filter" => {
+                        filter = (false, false);
+                        if let Some(component) = current.as_mut() {
+                            component.has_intent_filter = true;
+                        }
+                    }
+                    "action" => filter.0 |= attrs.get("name").is_some_and(|n| n == "android.intent.action.MAIN"),
+                    "category" => filter.1 |= attrs.get("name").is_some_and(|n| n == "android.intent.category.LAUNCHER"),
+                    tag if COMPONENT_TAGS.contains(&tag) => {
+                        current = Some(Component { tag: tag.to_string(), attrs, ..Default::default() });
+                    }
+                    _ => {}
+                }
+            }
+            Ok(XmlEvent::EndElement { name }) => match name.local_name.as_str() {
+                "intent-filter" => {
+                    if let Some(component) = current.as_mut() {
+                        component.launcher |= filter == (true, true);
+                    }
+                }
+                tag if COMPONENT_TAGS.contains(&tag) => components.extend(current.take()),
+                _ => {}
+            },
+            Err(err) => return Err(err.into()),
+            _ => {}
+        }
+    }
+
+    let target_sdk = target_sdk.unwrap_or(1);
+    let mut findings = vec![];
+    lint_application(&application, &package, target_sdk, &mut findings);
+    for component in &components {
+        lint_component(component, &package, target_sdk, &mut findings);
+    }
+    for permission in &permissions {
+        lint_permission(permission, &mut findings);
+    }
+    Ok(findings)
+}
+
+fn lint_application(attrs: &HashMap<String, String>, package: &str, target_sdk: u32, findings: &mut Vec<Finding>) {
+    let element = "application";
+    if bool_attr(attrs, "debuggable") == Some(true) {
+        findings.push(Finding::new(&DEBUGGABLE, element, "android:debuggable=\"true\" lets anyone attach a debugger"));
+    }
+    match bool_attr(attrs, "allowBackup") {
+        Some(true) => findings.push(Finding::new(&ALLOW_BACKUP, element, "android:allowBackup=\"true\" lets app data be copied off the device")),
+        None if !attrs.contains_key("allowBackup") => findings.push(Finding::new(&ALLOW_BACKUP, element, "android:allowBackup defaults to true")),
+        _ => {}
+    }
+    match bool_attr(attrs, "usesCleartextTraffic") {
+        Some(true) => findings.push(Finding::new(&CLEARTEXT, element, "android:usesCleartextTraffic=\"true\"")),
+        None if target_sdk < CLEARTEXT_DEFAULT_OFF_SDK && !attrs.contains_key("usesCleartextTraffic") && !attrs.contains_key("networkSecurityConfig") => findings.push(Finding::new(
+            &CLEARTEXT, element, format!("cleartext traffic is allowed by default for targetSdkVersion {}", target_sdk),
+        )),
+        _ => {}
+    }
+    lint_task_affinity(attrs, element, package, findings);
+}
+
+fn lint_component(component: &Component, package: &str, target_sdk: u32, findings: &mut Vec<Finding>) {
+    let attrs = &component.attrs;
+    let element = component.element();
+    let is_provider = component.tag == "provider";
+
+    let exported = match bool_attr(attrs, "exported") {
+        Some(exported) => exported,
+        // An unresolved reference: the value is unknown, so nothing is reported.
+        None if attrs.contains_key("exported") => false,
+        None if component.has_intent_filter && target_sdk >= EXPLICIT_EXPORTED_SDK => {
+            findings.push(Finding::new(&MISSING_EXPORTED, &element, format!(
+                "has an intent filter but no android:exported, required from targetSdkVersion {}", EXPLICIT_EXPORTED_SDK,
+            )));
+            // The app does not install, so whether it would be exported is moot.
+            false
+        }
+        None if is_provider => target_sdk < PROVIDER_DEFAULT_PRIVATE_SDK,
+        None => component.has_intent_filter,
+    };
+
+    if exported {
+        let protected = attrs.contains_key("permission")
+            || (is_provider && attrs.contains_key("readPermission") && attrs.contains_key("writePermission"));
+        if !protected && !component.launcher {
+            findings.push(Finding::new(&EXPORTED, &element, "is exported without android:permission"));
+        }
+        if is_provider && bool_attr(attrs, "grantUriPermissions") == Some(true) {
+            findings.push(Finding::new(&GRANT_URI, &element, "is exported and sets android:grantUriPermissions=\"true\""));
+        }
+    }
+    if component.tag == "activity" {
+        lint_task_affinity(attrs, &element, package, findings);
+    }
+}
+
+fn lint_task_affinity(attrs: &HashMap<String, String>, element: &str, package: &str, findings: &mut Vec<Finding>) {
+    if let Some(affinity) = attrs.get("taskAffinity") {
+        if !affinity.is_empty() && affinity != package {
+            findings.push(Finding::new(
+                &TASK_AFFINITY, format!("{} android:taskAffinity", element), format!("android:taskAffinity=\"{}\" differs from the package", affinity),
+            ));
+        }
+    }
+    if bool_attr(attrs, "allowTaskReparenting") == Some(true) {
+        findings.push(Finding::new(&TASK_AFFINITY, format!("{} android:allowTaskReparenting", element), "android:allowTaskReparenting=\"true\""));
+    }
+}
+
+fn lint_permission(attrs: &HashMap<String, String>, findings: &mut Vec<Finding>) {
+    let element = match attrs.get("name") {
+        Some(name) => format!("permission {}", name),
+        None => "permission".to_string(),
+    };
+    let level = attrs.get("protectionLevel").map_or("normal", String::as_str);
+    let base = match axml::int_value(level) {
+        Some(flags) => match flags & 0xf {
+            0 => "normal",
+            1 => "dangerous",
+            _ => "signature",
+        },
+        None => level.split('|').next().unwrap_or_default(),
+    };
+    if base == "normal" || base == "dangerous" {
+        findings.push(Finding::new(&WEAK_PERMISSION, element, format!("protectionLevel {} lets any app request it", base)));
+    }
+}
+
+/// Runs the network security config rules; `today` is a `YYYY-MM-DD` date.
+/// Debug overrides are ignored, they only apply to debuggable builds.
+pub fn lint_network_security_config(config: &NetworkSecurityConfig, today: &str) -> Vec<Finding> {
+    let mut findings = vec![];
+    let scopes = config.base.iter()
+        .map(|base| ("base-config".to_string(), base))
+        .chain(config.domains.iter().map(|domain| {
+            let names = domain.domains.iter().map(|d| d.name.as_str()).collect::<Vec<&str>>().join(", ");
+            (format!("domain-config {}", names), domain)
+        }));
+    for (scope, domain) in scopes {
+        let element = format!("{} {}", config.path, scope);
+        if domain.cleartext_permitted == Some(true) {
+            findings.push(Finding::new(&NSC_CLEARTEXT, &element, "cleartextTrafficPermitted=\"true\""));
+        }
+        if domain.trust_anchors.iter().any(|a| a.is_user()) {
+            findings.push(Finding::new(&NSC_USER_CA, &element, "trusts certificates from the user CA store"));
+        }
+        let expiration = domain.pin_set.as_ref().and_then(|p| p.expiration.as_deref());
+        if let Some(expiration) = expiration.filter(|e| *e < today) {
+            findings.push(Finding::new(&NSC_PINS_EXPIRED, &element, format!("pins expired on {} and are no longer enforced", expiration)));
+        }
+    }
+    findings
+}
+
+fn bool_attr(attrs: &HashMap<String, String>, name: &str) -> Option<bool> {
+    axml::bool_value(attrs.get(name)?)
+}
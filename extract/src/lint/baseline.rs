@@ -0,0 +1,50 @@
+use std::collections::BTreeSet;
+use super::LintReport;
+
+/// Accepted findings, which no longer fail a lint run. Stored as text, one
+/// `<rule ID><TAB><element>` fingerprint per line; `#` starts a comment.
+#[derive(Clone, Debug, Default)]
+pub struct Baseline {
+    pub fingerprints: BTreeSet<String>,
+}
+
+impl Baseline {
+    pub fn parse(text: &str) -> Baseline {
+        Baseline {
+            fingerprints: text.lines()
+                .map(str::trim_end)
+                .filter(|l| !l.is_empty() && !l.starts_with('#'))
+                .map(ToString::to_string)
+                .collect(),
+        }
+    }
+
+    /// A baseline accepting every current finding of `report`.
+    pub fn from_report(report: &LintReport) -> Baseline {
+        Baseline {
+            fingerprints: report.findings.iter()
+                .chain(&report.suppressed)
+                .map(|f| f.fingerprint())
+                .collect(),
+        }
+    }
+
+    pub fn to_text(&self) -> String {
+        let mut text = String::from("# app_extract_info lint baseline: <rule ID><TAB><element>\n");
+        for fingerprint in &self.fingerprints {
+            text.push_str(fingerprint);
+            text.push('\n');
+        }
+        text
+    }
+}
+
+impl LintReport {
+    /// Moves findings the baseline accepts to `suppressed`.
+    pub fn apply_baseline(&mut self, baseline: &Baseline) {
+        let (suppressed, findings) = self.findings.drain(..)
+            .partition(|f| baseline.fingerprints.contains(&f.fingerprint()));
+        self.findings = findings;
+        self.suppressed = suppressed;
+    }
+}
@@ -0,0 +1,120 @@
+use plist::{Dictionary, Value};
+use crate::{
+    codesign::{DistributionType, IpaSigningInfo},
+    macho::MachOInfo,
+};
+use super::{Finding, Rule, Severity};
+
+const ARBITRARY_LOADS: Rule = Rule { id: "IOS001", severity: Severity::High, title: "App Transport Security is disabled" };
+const ATS_EXCEPTION: Rule = Rule { id: "IOS002", severity: Severity::Medium, title: "App Transport Security exception for a domain" };
+const GET_TASK_ALLOW: Rule = Rule { id: "IOS003", severity: Severity::High, title: "Executable allows debugging (get-task-allow)" };
+const DEVELOPMENT_PROFILE: Rule = Rule { id: "IOS004", severity: Severity::Medium, title: "Signed with a development provisioning profile" };
+const NO_PIE: Rule = Rule { id: "IOS005", severity: Severity::High, title: "Executable is not position independent" };
+const NO_STACK_CANARY: Rule = Rule { id: "IOS006", severity: Severity::Medium, title: "Executable lacks stack protection" };
+const FILE_SHARING: Rule = Rule { id: "IOS007", severity: Severity::Low, title: "Documents are shared through the Files app or iTunes" };
+const URL_SCHEME_COLLISION: Rule = Rule { id: "IOS008", severity: Severity::Medium, title: "URL scheme collides with a well-known app" };
+
+pub const IOS_RULES: &[Rule] = &[
+    ARBITRARY_LOADS, ATS_EXCEPTION, GET_TASK_ALLOW, DEVELOPMENT_PROFILE, NO_PIE, NO_STACK_CANARY, FILE_SHARING, URL_SCHEME_COLLISION,
+];
+
+const ATS_KEY: &str = "NSAppTransportSecurity";
+const ARBITRARY_LOADS_KEYS: &[&str] = &["NSAllowsArbitraryLoads", "NSAllowsArbitraryLoadsForMedia", "NSAllowsArbitraryLoadsInWebContent"];
+const INSECURE_HTTP_KEYS: &[&str] = &["NSExceptionAllowsInsecureHTTPLoads", "NSTemporaryExceptionAllowsInsecureHTTPLoads"];
+const FORWARD_SECRECY_KEYS: &[&str] = &["NSExceptionRequiresForwardSecrecy", "NSTemporaryExceptionRequiresForwardSecrecy"];
+const MINIMUM_TLS_KEYS: &[&str] = &["NSExceptionMinimumTLSVersion", "NSTemporaryExceptionMinimumTLSVersion"];
+const WEAK_TLS_VERSIONS: &[&str] = &["TLSv1.0", "TLSv1.1"];
+
+/// Schemes owned by system or popular apps; registering one lets an app
+/// intercept links meant for them.
+const WELL_KNOWN_SCHEMES: &[&str] = &[
+    "http", "https", "mailto", "tel", "sms", "facetime", "facetime-audio", "maps", "itms", "itms-apps", "itms-services",
+    "fb", "fbauth2", "fb-messenger", "instagram", "twitter", "whatsapp", "tg", "line", "weixin", "wechat", "alipay",
+    "mqq", "paypal", "venmo", "googlechrome", "comgooglemaps", "youtube", "spotify", "slack", "zoomus", "snapchat",
+];
+
+/// Runs the iOS rules over the main Info.plist.
+pub fn lint_info_plist(plist: &Dictionary) -> Vec<Finding> {
+    let mut findings = vec![];
+
+    if let Some(ats) = plist.get(ATS_KEY).and_then(Value::as_dictionary) {
+        for key in ARBITRARY_LOADS_KEYS {
+            if ats.get(key).and_then(Value::as_boolean) == Some(true) {
+                findings.push(Finding::new(&ARBITRARY_LOADS, format!("{}.{}", ATS_KEY, key), "allows cleartext and unvalidated TLS connections"));
+            }
+        }
+        let domains = ats.get("NSExceptionDomains").and_then(Value::as_dictionary);
+        for (domain, exception) in domains.into_iter().flatten() {
+            let element = format!("{}.NSExceptionDomains.{}", ATS_KEY, domain);
+            let exception = match exception.as_dictionary() {
+                Some(exception) => exception,
+                None => continue,
+            };
+            // The key is part of the element so each exception is baselined on its own.
+            let flag = |keys: &[&'static str]| keys.iter().find_map(|k| exception.get(k).and_then(Value::as_boolean).map(|v| (*k, v)));
+            if let Some((key, true)) = flag(INSECURE_HTTP_KEYS) {
+                findings.push(Finding::new(&ATS_EXCEPTION, format!("{}.{}", element, key), "allows insecure HTTP loads"));
+            }
+            if let Some((key, false)) = flag(FORWARD_SECRECY_KEYS) {
+                findings.push(Finding::new(&ATS_EXCEPTION, format!("{}.{}", element, key), "does not require forward secrecy"));
+            }
+            let tls = MINIMUM_TLS_KEYS.iter().find_map(|k| exception.get(k).and_then(Value::as_string).map(|v| (*k, v)));
+            if let Some((key, tls)) = tls.filter(|(_, tls)| WEAK_TLS_VERSIONS.contains(tls)) {
+                findings.push(Finding::new(&ATS_EXCEPTION, format!("{}.{}", element, key), format!("accepts {}", tls)));
+            }
+        }
+    }
+
+    for key in ["UIFileSharingEnabled", "LSSupportsOpeningDocumentsInPlace"] {
+        if plist.get(key).and_then(Value::as_boolean) == Some(true) {
+            findings.push(Finding::new(&FILE_SHARING, key, "exposes the Documents directory to the user"));
+        }
+    }
+
+    let schemes = plist.get("CFBundleURLTypes")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(Value::as_dictionary)
+        .filter_map(|t| t.get("CFBundleURLSchemes").and_then(Value::as_array))
+        .flatten()
+        .filter_map(Value::as_string);
+    for scheme in schemes {
+        if WELL_KNOWN_SCHEMES.contains(&scheme.to_lowercase().as_str()) {
+            findings.push(Finding::new(&URL_SCHEME_COLLISION, format!("CFBundleURLTypes.{}", scheme), format!("registers the {}:// scheme", scheme)));
+        }
+    }
+
+    findings
+}
+
+/// Runs the iOS rules over the code signature and provisioning profile.
+pub fn lint_signing(info: &IpaSigningInfo) -> Vec<Finding> {
+    let mut findings = vec![];
+    for signature in &info.code_signatures {
+        if signature.entitlements.get("get-task-allow").and_then(Value::as_boolean) == Some(true) {
+            findings.push(Finding::new(&GET_TASK_ALLOW, format!("entitlements ({})", signature.arch), "get-task-allow is true"));
+        }
+    }
+    if let Some(profile) = &info.profile {
+        if profile.distribution == DistributionType::Development {
+            findings.push(Finding::new(&DEVELOPMENT_PROFILE, "embedded.mobileprovision", format!("profile {} is for development", profile.name)));
+        }
+    }
+    findings
+}
+
+/// Runs the iOS rules over the main executable.
+pub fn lint_executable(info: &MachOInfo) -> Vec<Finding> {
+    let mut findings = vec![];
+    for slice in &info.slices {
+        let element = format!("{} ({})", info.path, slice.arch);
+        if !slice.pie {
+            findings.push(Finding::new(&NO_PIE, &element, "MH_PIE is not set, ASLR is ineffective"));
+        }
+        if !slice.stack_canary {
+            findings.push(Finding::new(&NO_STACK_CANARY, &element, "neither ___stack_chk_guard nor ___stack_chk_fail is referenced"));
+        }
+    }
+    findings
+}
@@ -0,0 +1,47 @@
+use super::LintReport;
+
+impl LintReport {
+    /// The report as JUnit XML: one test case per rule, failing with the
+    /// rule's findings. Rules with only suppressed findings are skipped.
+    pub fn to_junit(&self, artifact: &str) -> String {
+        let mut cases = String::new();
+        let mut failures = 0;
+        let mut skipped = 0;
+        for rule in self.rules {
+            cases.push_str(&format!(
+                "    <testcase classname=\"{}\" name=\"{} {}\">\n",
+                escape(artifact), rule.id, escape(rule.title)
+            ));
+            let findings: Vec<String> = self.findings.iter()
+                .filter(|f| f.rule_id == rule.id)
+                .map(|f| format!("[{}] {}: {}", f.severity, f.element, f.message))
+                .collect();
+            if !findings.is_empty() {
+                failures += 1;
+                cases.push_str(&format!(
+                    "      <failure type=\"{}\" message=\"{} finding(s)\">{}</failure>\n",
+                    rule.severity, findings.len(), escape(&findings.join("\n"))
+                ));
+            } else if self.suppressed.iter().any(|f| f.rule_id == rule.id) {
+                skipped += 1;
+                cases.push_str("      <skipped message=\"suppressed by the baseline\"/>\n");
+            }
+            cases.push_str("    </testcase>\n");
+        }
+
+        format!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+             <testsuites>\n  \
+             <testsuite name=\"{} lint\" tests=\"{}\" failures=\"{}\" skipped=\"{}\">\n{}  </testsuite>\n\
+             </testsuites>\n",
+            env!("CARGO_PKG_NAME"), self.rules.len(), failures, skipped, cases
+        )
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
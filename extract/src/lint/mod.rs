@@ -0,0 +1,114 @@
+use core::fmt;
+
+mod android;
+mod baseline;
+mod ios;
+mod junit;
+mod sarif;
+
+/// How much a finding matters, lowest first.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Severity {
+    Info,
+    Low,
+    Medium,
+    High,
+}
+
+impl Severity {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Severity::Info => "info",
+            Severity::Low => "low",
+            Severity::Medium => "medium",
+            Severity::High => "high",
+        }
+    }
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// A check the linter runs, with the severity of its findings.
+#[derive(Clone, Copy, Debug)]
+pub struct Rule {
+    pub id: &'static str,
+    pub severity: Severity,
+    pub title: &'static str,
+}
+
+/// One problem found by a rule.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Finding {
+    pub rule_id: &'static str,
+    pub severity: Severity,
+    /// The manifest element, plist key or file the finding is about.
+    pub element: String,
+    pub message: String,
+}
+
+impl Finding {
+    pub(crate) fn new(rule: &Rule, element: impl Into<String>, message: impl Into<String>) -> Finding {
+        Finding {
+            rule_id: rule.id,
+            severity: rule.severity,
+            element: element.into(),
+            message: message.into(),
+        }
+    }
+
+    /// Identifies the finding across runs; messages may mention dates or
+    /// versions, so only the rule and element take part. Rules that can report
+    /// several problems on one element name the attribute or key in the element.
+    pub fn fingerprint(&self) -> String {
+        format!("{}\t{}", self.rule_id, self.element)
+    }
+}
+
+/// Findings of all rules over one app.
+#[derive(Clone, Debug, Default)]
+pub struct LintReport {
+    /// The rules that were run.
+    pub rules: &'static [Rule],
+    pub findings: Vec<Finding>,
+    /// Findings accepted by a baseline.
+    pub suppressed: Vec<Finding>,
+}
+
+impl LintReport {
+    /// Most severe findings first, then by rule.
+    pub fn sort(&mut self) {
+        self.findings.sort_by(|a, b| b.severity.cmp(&a.severity).then(a.rule_id.cmp(b.rule_id)));
+    }
+}
+
+/// Looks up a rule by ID.
+pub fn rule(id: &str) -> Option<&'static Rule> {
+    ANDROID_RULES.iter().chain(IOS_RULES).find(|r| r.id == id)
+}
+
+impl fmt::Display for Finding {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "[{}] {} {}: {}", self.severity, self.rule_id, self.element, self.message)
+    }
+}
+
+impl fmt::Display for LintReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for finding in &self.findings {
+            writeln!(f, "{}", finding)?;
+        }
+        if self.suppressed.is_empty() {
+            writeln!(f, "{} finding(s)", self.findings.len())
+        } else {
+            writeln!(f, "{} finding(s), {} suppressed by the baseline", self.findings.len(), self.suppressed.len())
+        }
+    }
+}
+
+pub use baseline::Baseline;
+pub use android::{ANDROID_RULES, lint_android_manifest, lint_network_security_config};
+pub use ios::{IOS_RULES, lint_info_plist, lint_signing, lint_executable};
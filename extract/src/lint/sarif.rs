@@ -0,0 +1,69 @@
+use serde_json::{json, Value};
+use super::{Finding, LintReport, Severity};
+
+const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+const FINGERPRINT_KEY: &str = "appExtractInfo/v1";
+
+fn level(severity: Severity) -> &'static str {
+    match severity {
+        Severity::High => "error",
+        Severity::Medium => "warning",
+        Severity::Low | Severity::Info => "note",
+    }
+}
+
+impl LintReport {
+    /// The report as a SARIF 2.1.0 log, with `artifact` as the analysed file.
+    /// Baseline suppressed findings are kept, marked as externally suppressed.
+    pub fn to_sarif(&self, artifact: &str) -> String {
+        let rules: Vec<Value> = self.rules.iter()
+            .map(|rule| json!({
+                "id": rule.id,
+                "shortDescription": { "text": rule.title },
+                "defaultConfiguration": { "level": level(rule.severity) },
+                "properties": { "severity": rule.severity.name() },
+            }))
+            .collect();
+
+        let result = |finding: &Finding, suppressed: bool| {
+            let mut result = json!({
+                "ruleId": finding.rule_id,
+                "level": level(finding.severity),
+                "message": { "text": format!("{}: {}", finding.element, finding.message) },
+                "locations": [{
+                    "physicalLocation": { "artifactLocation": { "uri": artifact } },
+                    "logicalLocations": [{ "fullyQualifiedName": finding.element }],
+                }],
+                "partialFingerprints": { FINGERPRINT_KEY: finding.fingerprint() },
+            });
+            if let Some(index) = self.rules.iter().position(|r| r.id == finding.rule_id) {
+                result["ruleIndex"] = json!(index);
+            }
+            if suppressed {
+                result["suppressions"] = json!([{ "kind": "external" }]);
+            }
+            result
+        };
+        let results: Vec<Value> = self.findings.iter()
+            .map(|f| result(f, false))
+            .chain(self.suppressed.iter().map(|f| result(f, true)))
+            .collect();
+
+        let log = json!({
+            "$schema": SARIF_SCHEMA,
+            "version": "2.1.0",
+            "runs": [{
+                "tool": {
+                    "driver": {
+                        "name": env!("CARGO_PKG_NAME"),
+                        "version": env!("CARGO_PKG_VERSION"),
+                        "rules": rules,
+                    },
+                },
+                "artifacts": [{ "location": { "uri": artifact } }],
+                "results": results,
+            }],
+        });
+        serde_json::to_string_pretty(&log).unwrap_or_default()
+    }
+}
@@ -0,0 +1,81 @@
+use core::fmt;
+use crate::{
+    bytes::ByteReader,
+    error::ExtResult,
+};
+use super::{MachO, LC_ENCRYPTION_INFO, LC_ENCRYPTION_INFO_64};
+
+/// FairPlay encryption state of a Mach-O file, per architecture.
+#[derive(Clone, Debug)]
+pub struct EncryptionInfo {
+    /// Path inside the archive.
+    pub path: String,
+    pub slices: Vec<SliceEncryption>,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct SliceEncryption {
+    pub arch: String,
+    /// `None` without an `LC_ENCRYPTION_INFO(_64)` command, non-zero while encrypted.
+    pub cryptid: Option<u32>,
+    pub cryptoff: u32,
+    pub cryptsize: u32,
+}
+
+impl EncryptionInfo {
+    pub fn parse(path: &str, buf: &[u8]) -> ExtResult<EncryptionInfo> {
+        let slices = MachO::parse_all(buf)?
+            .iter()
+            .map(SliceEncryption::from_macho)
+            .collect::<ExtResult<Vec<SliceEncryption>>>()?;
+        Ok(EncryptionInfo {
+            path: path.to_string(),
+            slices,
+        })
+    }
+
+    /// Whether any architecture is still encrypted, as App Store downloads are.
+    pub fn encrypted(&self) -> bool {
+        self.slices.iter().any(|s| s.cryptid.is_some_and(|id| id != 0))
+    }
+}
+
+impl SliceEncryption {
+    pub(crate) fn from_macho(macho: &MachO) -> ExtResult<SliceEncryption> {
+        let mut slice = SliceEncryption {
+            arch: macho.arch(),
+            ..Default::default()
+        };
+        let command = macho.command(LC_ENCRYPTION_INFO)
+            .or_else(|| macho.command(LC_ENCRYPTION_INFO_64));
+        if let Some(command) = command {
+            let mut r = ByteReader::at(command.data, 8);
+            slice.cryptoff = r.u32()?;
+            slice.cryptsize = r.u32()?;
+            slice.cryptid = Some(r.u32()?);
+        }
+        Ok(slice)
+    }
+}
+
+impl fmt::Display for EncryptionInfo {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for slice in &self.slices {
+            writeln!(f, "{} ({}): {}", self.path, slice.arch, slice)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for SliceEncryption {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.cryptid {
+            Some(cryptid) => write!(
+                f,
+                "{} (cryptid {}, offset {:#x}, size {:#x})",
+                if cryptid != 0 { "encrypted" } else { "decrypted" }, cryptid, self.cryptoff, self.cryptsize
+            ),
+            None => write!(f, "no encryption info"),
+        }
+    }
+}
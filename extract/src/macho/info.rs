@@ -0,0 +1,155 @@
+use core::fmt;
+use crate::{
+    bytes::ByteReader,
+    error::ExtResult,
+};
+use super::{
+    is_fat, MachO, MH_PIE,
+    LC_SYMTAB, LC_BUILD_VERSION,
+    LC_VERSION_MIN_MACOSX, LC_VERSION_MIN_IPHONEOS, LC_VERSION_MIN_TVOS, LC_VERSION_MIN_WATCHOS,
+    LC_LOAD_DYLIB, LC_LOAD_WEAK_DYLIB, LC_REEXPORT_DYLIB, LC_LAZY_LOAD_DYLIB, LC_LOAD_UPWARD_DYLIB,
+};
+
+const STACK_CHECK_SYMBOLS: &[&[u8]] = &[b"___stack_chk_guard", b"___stack_chk_fail"];
+const ARC_SYMBOLS: &[&[u8]] = &[b"_objc_release", b"_objc_retain", b"_objc_autoreleaseReturnValue", b"_swift_release"];
+
+/// What a Mach-O file is built for and with, per architecture.
+#[derive(Clone, Debug)]
+pub struct MachOInfo {
+    /// Path inside the archive.
+    pub path: String,
+    pub fat: bool,
+    pub slices: Vec<SliceInfo>,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct SliceInfo {
+    pub arch: String,
+    pub platform: Option<String>,
+    pub min_os: Option<String>,
+    pub sdk: Option<String>,
+    pub pie: bool,
+    pub stack_canary: bool,
+    pub arc: bool,
+    /// Install names of the linked dylibs and frameworks, in load order.
+    pub dylibs: Vec<String>,
+}
+
+impl MachOInfo {
+    pub fn parse(path: &str, buf: &[u8]) -> ExtResult<MachOInfo> {
+        let slices = MachO::parse_all(buf)?
+            .iter()
+            .map(SliceInfo::from_macho)
+            .collect::<ExtResult<Vec<SliceInfo>>>()?;
+        Ok(MachOInfo {
+            path: path.to_string(),
+            fat: is_fat(buf),
+            slices,
+        })
+    }
+}
+
+impl SliceInfo {
+    pub(crate) fn from_macho(macho: &MachO) -> ExtResult<SliceInfo> {
+        let mut info = SliceInfo {
+            arch: macho.arch(),
+            pie: macho.flags & MH_PIE != 0,
+            ..Default::default()
+        };
+
+        for command in &macho.commands {
+            let mut r = ByteReader::at(command.data, 8);
+            match command.cmd {
+                LC_BUILD_VERSION => {
+                    info.platform = Some(platform_name(r.u32()?));
+                    info.min_os = Some(format_version(r.u32()?));
+                    info.sdk = Some(format_version(r.u32()?));
+                }
+                LC_VERSION_MIN_MACOSX | LC_VERSION_MIN_IPHONEOS | LC_VERSION_MIN_TVOS | LC_VERSION_MIN_WATCHOS => {
+                    let platform = match command.cmd {
+                        LC_VERSION_MIN_MACOSX => "macOS",
+                        LC_VERSION_MIN_IPHONEOS => "iOS",
+                        LC_VERSION_MIN_TVOS => "tvOS",
+                        _ => "watchOS",
+                    };
+                    info.platform = Some(platform.to_string());
+                    info.min_os = Some(format_version(r.u32()?));
+                    info.sdk = Some(format_version(r.u32()?));
+                }
+                LC_LOAD_DYLIB | LC_LOAD_WEAK_DYLIB | LC_REEXPORT_DYLIB | LC_LAZY_LOAD_DYLIB | LC_LOAD_UPWARD_DYLIB => {
+                    let offset = r.u32()? as usize;
+                    let name = command.data.get(offset..).unwrap_or_default();
+                    let end = name.iter().position(|b| *b == 0).unwrap_or(name.len());
+                    info.dylibs.push(String::from_utf8_lossy(&name[..end]).into_owned());
+                }
+                LC_SYMTAB => {
+                    let _symoff = r.u32()?;
+                    let _nsyms = r.u32()?;
+                    let stroff = r.u32()? as usize;
+                    let strsize = r.u32()? as usize;
+                    let strings = ByteReader::at(macho.data, stroff).bytes(strsize)?;
+                    let has_any = |names: &[&[u8]]| strings.split(|b| *b == 0).any(|s| names.contains(&s));
+                    info.stack_canary = has_any(STACK_CHECK_SYMBOLS);
+                    info.arc = has_any(ARC_SYMBOLS);
+                }
+                _ => {}
+            }
+        }
+
+        Ok(info)
+    }
+}
+
+/// `xxxx.yy.zz` nibble-packed version, without a zero patch level.
+fn format_version(v: u32) -> String {
+    let (major, minor, patch) = (v >> 16, (v >> 8) & 0xff, v & 0xff);
+    if patch == 0 {
+        format!("{}.{}", major, minor)
+    } else {
+        format!("{}.{}.{}", major, minor, patch)
+    }
+}
+
+fn platform_name(platform: u32) -> String {
+    match platform {
+        1 => "macOS".into(),
+        2 => "iOS".into(),
+        3 => "tvOS".into(),
+        4 => "watchOS".into(),
+        5 => "bridgeOS".into(),
+        6 => "Mac Catalyst".into(),
+        7 => "iOS Simulator".into(),
+        8 => "tvOS Simulator".into(),
+        9 => "watchOS Simulator".into(),
+        10 => "DriverKit".into(),
+        11 => "visionOS".into(),
+        12 => "visionOS Simulator".into(),
+        other => format!("platform {}", other),
+    }
+}
+
+impl fmt::Display for MachOInfo {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "{}: {} Mach-O", self.path, if self.fat { "universal" } else { "thin" })?;
+        for slice in &self.slices {
+            write!(f, "{}", slice)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for SliceInfo {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let unknown = "unknown";
+        writeln!(f, "{} platform: {}", self.arch, self.platform.as_deref().unwrap_or(unknown))?;
+        writeln!(f, "{} minimum OS: {}", self.arch, self.min_os.as_deref().unwrap_or(unknown))?;
+        writeln!(f, "{} SDK: {}", self.arch, self.sdk.as_deref().unwrap_or(unknown))?;
+        writeln!(f, "{} PIE: {}", self.arch, self.pie)?;
+        writeln!(f, "{} stack canary: {}", self.arch, self.stack_canary)?;
+        writeln!(f, "{} ARC: {}", self.arch, self.arc)?;
+        for dylib in &self.dylibs {
+            writeln!(f, "{} links: {}", self.arch, dylib)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,164 @@
+mod encryption;
+mod info;
+
+use crate::{
+    bytes::ByteReader,
+    error::{Error, ExtResult},
+};
+
+const FAT_MAGIC: u32 = 0xcafe_babe;
+const FAT_MAGIC_64: u32 = 0xcafe_babf;
+const MH_MAGIC: u32 = 0xfeed_face;
+const MH_MAGIC_64: u32 = 0xfeed_facf;
+
+pub const LC_SYMTAB: u32 = 0x02;
+pub const LC_LOAD_DYLIB: u32 = 0x0c;
+pub const LC_CODE_SIGNATURE: u32 = 0x1d;
+pub const LC_LAZY_LOAD_DYLIB: u32 = 0x20;
+pub const LC_ENCRYPTION_INFO: u32 = 0x21;
+pub const LC_VERSION_MIN_MACOSX: u32 = 0x24;
+pub const LC_VERSION_MIN_IPHONEOS: u32 = 0x25;
+pub const LC_VERSION_MIN_TVOS: u32 = 0x2f;
+pub const LC_ENCRYPTION_INFO_64: u32 = 0x2c;
+pub const LC_VERSION_MIN_WATCHOS: u32 = 0x30;
+pub const LC_BUILD_VERSION: u32 = 0x32;
+pub const LC_LOAD_WEAK_DYLIB: u32 = 0x8000_0018;
+pub const LC_REEXPORT_DYLIB: u32 = 0x8000_001f;
+pub const LC_LOAD_UPWARD_DYLIB: u32 = 0x8000_0023;
+
+pub const MH_PIE: u32 = 0x0020_0000;
+
+const CPU_ARCH_ABI64: u32 = 0x0100_0000;
+const CPU_ARCH_ABI64_32: u32 = 0x0200_0000;
+const CPU_TYPE_X86: u32 = 7;
+const CPU_TYPE_ARM: u32 = 12;
+
+/// One architecture of a (possibly universal) Mach-O file.
+#[derive(Clone, Debug)]
+pub(crate) struct MachO<'a> {
+    /// The whole slice; load command offsets are relative to it.
+    pub data: &'a [u8],
+    pub cpu_type: u32,
+    pub cpu_subtype: u32,
+    pub flags: u32,
+    pub commands: Vec<LoadCommand<'a>>,
+}
+
+/// A load command, `data` starting with its `cmd` and `cmdsize` fields.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct LoadCommand<'a> {
+    pub cmd: u32,
+    pub data: &'a [u8],
+}
+
+impl<'a> MachO<'a> {
+    /// Splits a universal binary into its slices, or parses a thin one.
+    pub fn parse_all(buf: &'a [u8]) -> ExtResult<Vec<MachO<'a>>> {
+        let mut r = ByteReader::at(buf, 0).big_endian();
+        let magic = r.u32()?;
+        if !is_fat(buf) {
+            return Ok(vec![MachO::parse(buf)?]);
+        }
+
+        let count = r.u32()?;
+        let mut slices = vec![];
+        for _ in 0..count {
+            let _cpu_type = r.u32()?;
+            let _cpu_subtype = r.u32()?;
+            let (offset, size) = if magic == FAT_MAGIC_64 {
+                let offset = r.u64()? as usize;
+                let size = r.u64()? as usize;
+                r.skip(8)?;
+                (offset, size)
+            } else {
+                let offset = r.u32()? as usize;
+                let size = r.u32()? as usize;
+                r.skip(4)?;
+                (offset, size)
+            };
+            let end = offset.checked_add(size).ok_or(Error::UnexpectedEof)?;
+            slices.push(MachO::parse(buf.get(offset..end).ok_or(Error::UnexpectedEof)?)?);
+        }
+        Ok(slices)
+    }
+
+    pub fn parse(data: &'a [u8]) -> ExtResult<MachO<'a>> {
+        let mut r = ByteReader::at(data, 0);
+        let is_64 = match r.u32()? {
+            MH_MAGIC => false,
+            MH_MAGIC_64 => true,
+            _ => return Err(invalid("not a little-endian Mach-O file")),
+        };
+        let cpu_type = r.u32()?;
+        let cpu_subtype = r.u32()?;
+        let _file_type = r.u32()?;
+        let ncmds = r.u32()?;
+        let _sizeofcmds = r.u32()?;
+        let flags = r.u32()?;
+        if is_64 {
+            r.skip(4)?;
+        }
+
+        let mut commands = vec![];
+        for _ in 0..ncmds {
+            let start = r.pos();
+            let cmd = r.u32()?;
+            let size = r.u32()? as usize;
+            if size < 8 {
+                return Err(invalid("load command too small"));
+            }
+            r.seek(start)?;
+            commands.push(LoadCommand { cmd, data: r.bytes(size)? });
+        }
+
+        Ok(MachO { data, cpu_type, cpu_subtype, flags, commands })
+    }
+
+    pub fn command(&self, cmd: u32) -> Option<&LoadCommand<'a>> {
+        self.commands.iter().find(|c| c.cmd == cmd)
+    }
+
+    pub fn arch(&self) -> String {
+        arch_name(self.cpu_type, self.cpu_subtype)
+    }
+}
+
+/// Whether `buf` starts with a universal binary header.
+pub fn is_fat(buf: &[u8]) -> bool {
+    matches!(ByteReader::at(buf, 0).big_endian().u32(), Ok(FAT_MAGIC | FAT_MAGIC_64))
+}
+
+/// Whether `buf` starts like a Mach-O file. Java class files share the
+/// universal magic, but their version is where the slice count would be.
+pub fn is_macho(buf: &[u8]) -> bool {
+    let mut r = ByteReader::at(buf, 0);
+    match r.u32() {
+        Ok(MH_MAGIC | MH_MAGIC_64) => true,
+        _ => is_fat(buf) && ByteReader::at(buf, 4).big_endian().u32().is_ok_and(|count| count < 45),
+    }
+}
+
+/// The name `lipo` uses for a CPU type and subtype.
+pub fn arch_name(cpu_type: u32, cpu_subtype: u32) -> String {
+    let subtype = cpu_subtype & 0x00ff_ffff;
+    match (cpu_type, subtype) {
+        (CPU_TYPE_X86, _) => "i386".into(),
+        (t, _) if t == CPU_TYPE_X86 | CPU_ARCH_ABI64 => "x86_64".into(),
+        (CPU_TYPE_ARM, 6) => "armv6".into(),
+        (CPU_TYPE_ARM, 9) => "armv7".into(),
+        (CPU_TYPE_ARM, 11) => "armv7s".into(),
+        (CPU_TYPE_ARM, 12) => "armv7k".into(),
+        (CPU_TYPE_ARM, _) => "arm".into(),
+        (t, 2) if t == CPU_TYPE_ARM | CPU_ARCH_ABI64 => "arm64e".into(),
+        (t, _) if t == CPU_TYPE_ARM | CPU_ARCH_ABI64 => "arm64".into(),
+        (t, _) if t == CPU_TYPE_ARM | CPU_ARCH_ABI64_32 => "arm64_32".into(),
+        _ => format!("cpu {:#x}/{:#x}", cpu_type, cpu_subtype),
+    }
+}
+
+pub(crate) fn invalid(msg: &str) -> Error {
+    Error::InvalidMachO(msg.to_string())
+}
+
+pub use encryption::{EncryptionInfo, SliceEncryption};
+pub use info::{MachOInfo, SliceInfo};
@@ -1,16 +1,189 @@
 use std::{
+    collections::BTreeMap,
     env,
-    path::PathBuf
+    fs,
+    path::PathBuf,
+    process,
 };
 use app_extract_info::{
     error::{ExtResult},
     get_loaders,
+    get_loaders_for,
+    get_resource_table,
+    get_signing_info,
+    get_ipa_signing_info,
+    check_ipa_profile,
+    get_ipa_executable_info,
+    get_ipa_encryption_info,
+    get_ipa_bundle_inventory,
+    manifest::IPA_EXT,
+    verify_signatures,
+    write_channel,
+    lint,
+    lint::Baseline,
+    evaluate_policy,
+    policy::Policy,
+    get_network_security_config,
+    get_native_libraries,
+    check_page_size,
+    get_dex_files,
+    detect_sdks,
+    resource::DeviceConfig,
 };
 
+const USAGE: &str = "usage: app_extract_info [info <file> [qualifiers] | resources <apk> | signatures <apk|ipa> | verify <apk|ipa> | binary <ipa> | encryption <ipa> | bundle <ipa> | privacy <ipa> | lint <apk|ipa> [--format text|sarif|junit] [--baseline <file>] [--write-baseline <file>] | netsec <apk> | libs <apk> | pagesize <apk> | dex <apk> [--depth <n>] | sdks <apk|ipa> | policy <policy.toml> <apk|ipa> | channel <apk> <output> <channel> [key=value ...]]";
+
 fn main() -> ExtResult<()> {
-    let base_dir = env::current_dir().expect("not found path");
-    test_ipa(base_dir.clone());
-    test_apk(base_dir.clone());
+    let args: Vec<String> = env::args().skip(1).collect();
+
+    match (args.first().map(String::as_str), args.get(1)) {
+        (Some("info"), Some(path)) => {
+            let manifest = match args.get(2) {
+                Some(qualifiers) => get_loaders_for(&PathBuf::from(path), &qualifiers.parse::<DeviceConfig>()?)?,
+                None => get_loaders(&PathBuf::from(path))?,
+            };
+            println!("{:?}", manifest);
+            if let Some(requirements) = &manifest.requirements {
+                print!("{}", requirements);
+            }
+        }
+        (Some("resources"), Some(path)) => {
+            print!("{}", get_resource_table(&PathBuf::from(path))?);
+        }
+        (Some("signatures"), Some(path)) => {
+            let path = PathBuf::from(path);
+            if path.extension().is_some_and(|ext| ext == IPA_EXT) {
+                print!("{}", get_ipa_signing_info(&path)?);
+            } else {
+                print!("{}", get_signing_info(&path)?);
+            }
+        }
+        (Some("verify"), Some(path)) => {
+            let path = PathBuf::from(path);
+            if path.extension().is_some_and(|ext| ext == IPA_EXT) {
+                print!("{}", check_ipa_profile(&path)?);
+            } else {
+                print!("{}", verify_signatures(&path)?);
+            }
+        }
+        (Some("binary"), Some(path)) => {
+            match get_ipa_executable_info(&PathBuf::from(path))? {
+                Some(info) => print!("{}", info),
+                None => eprintln!("No main executable found"),
+            }
+        }
+        (Some("encryption"), Some(path)) => {
+            for info in get_ipa_encryption_info(&PathBuf::from(path))? {
+                print!("{}", info);
+            }
+        }
+        (Some("bundle"), Some(path)) => {
+            print!("{}", get_ipa_bundle_inventory(&PathBuf::from(path))?);
+        }
+        (Some("privacy"), Some(path)) => {
+            if let Some(privacy) = get_loaders(&PathBuf::from(path))?.privacy {
+                print!("{}", privacy);
+            }
+        }
+        (Some("lint"), Some(path)) => {
+            let mut format = "text";
+            let mut baseline = None;
+            let mut write_baseline = None;
+            let mut options = args[2..].iter();
+            while let Some(option) = options.next() {
+                match (option.as_str(), options.next()) {
+                    ("--format", Some(value)) => format = value,
+                    ("--baseline", Some(file)) => baseline = Some(file),
+                    ("--write-baseline", Some(file)) => write_baseline = Some(file),
+                    _ => {
+                        eprintln!("{}", USAGE);
+                        process::exit(2);
+                    }
+                }
+            }
+
+            let mut report = lint(&PathBuf::from(path))?;
+            // Recording a baseline accepts every current finding, so it succeeds.
+            if let Some(file) = write_baseline {
+                fs::write(file, Baseline::from_report(&report).to_text())?;
+                println!("Wrote {} finding(s) to {}", report.findings.len(), file);
+                return Ok(());
+            }
+            if let Some(file) = baseline {
+                report.apply_baseline(&Baseline::parse(&fs::read_to_string(file)?));
+            }
+            match format {
+                "sarif" => println!("{}", report.to_sarif(path)),
+                "junit" => print!("{}", report.to_junit(path)),
+                _ => print!("{}", report),
+            }
+            if !report.findings.is_empty() {
+                process::exit(1);
+            }
+        }
+        (Some("netsec"), Some(path)) => {
+            match get_network_security_config(&PathBuf::from(path))? {
+                Some(config) => print!("{}", config),
+                None => println!("No network security config"),
+            }
+        }
+        (Some("policy"), Some(policy)) if args.len() >= 3 => {
+            let policy = Policy::from_toml(&fs::read_to_string(policy)?)?;
+            let report = evaluate_policy(&PathBuf::from(&args[2]), &policy)?;
+            print!("{}", report);
+            if !report.passed() {
+                process::exit(1);
+            }
+        }
+        (Some("libs"), Some(path)) => {
+            print!("{}", get_native_libraries(&PathBuf::from(path))?);
+        }
+        (Some("pagesize"), Some(path)) => {
+            let report = check_page_size(&PathBuf::from(path))?;
+            print!("{}", report);
+            if !report.compliant() {
+                process::exit(1);
+            }
+        }
+        (Some("dex"), Some(path)) => {
+            let dex = get_dex_files(&PathBuf::from(path))?;
+            let depth = match (args.get(2).map(String::as_str), args.get(3).map(|n| n.parse())) {
+                (None, _) => 2,
+                (Some("--depth"), Some(Ok(depth))) => depth,
+                _ => {
+                    eprintln!("{}", USAGE);
+                    process::exit(2);
+                }
+            };
+            print!("{}", dex);
+            println!("Methods by package:");
+            for (package, count) in dex.packages(depth) {
+                println!("  {}: {}", if package.is_empty() { "(default)" } else { &package }, count);
+            }
+        }
+        (Some("sdks"), Some(path)) => {
+            print!("{}", detect_sdks(&PathBuf::from(path))?);
+        }
+        (Some("channel"), Some(path)) if args.len() >= 4 => {
+            let extras = args[4..].iter()
+                .filter_map(|kv| kv.split_once('='))
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect::<BTreeMap<String, String>>();
+            write_channel(&PathBuf::from(path), &PathBuf::from(&args[2]), &args[3], &extras)?;
+            let idsig = format!("{}.idsig", path);
+            if PathBuf::from(&idsig).exists() {
+                eprintln!("warning: {} does not match {}, its v4 signature must be regenerated", idsig, args[2]);
+            }
+        }
+        (None, _) => {
+            let base_dir = env::current_dir().expect("not found path");
+            test_ipa(base_dir.clone());
+            test_apk(base_dir.clone());
+        }
+        _ => {
+            eprintln!("{}", USAGE);
+        }
+    }
 
     Ok(())
 }
@@ -39,4 +212,4 @@ fn test_apk(base_dir: PathBuf) {
             println!("{:?}", err);
         }
     }
-}
\ No newline at end of file
+}
@@ -1,82 +1,32 @@
-use std::{io::Cursor};
 use xml::{EventReader, reader::XmlEvent};
 use crate::{
     error::{ExtResult},
+    resource::{ResourceId, ResourceTable, EntryValue, DeviceConfig},
 };
-use super::Manifest;
+use super::{Manifest, RESOURCE_VALUE_TYPE};
 
 pub const APK_EXT: &str = "apk";
 
-pub struct ResourceId {
-    id: u32,
-}
-
-impl ResourceId {
-    pub fn from_parts(package_id: u8, type_id: u8, entry_id: u16) -> ResourceId {
-        ResourceId {
-            id: ((package_id as u32) << 24) | ((type_id as u32) << 16) | entry_id as u32,
-        }
-    }
-
-    pub fn from_u32(id: u32) -> ResourceId {
-        ResourceId { id }
-    }
+pub(crate) fn find_resource_by_id (table: &ResourceTable, key: String, device: Option<&DeviceConfig>) -> Option<String> {
+    let id = ResourceId::from_u32(key.replace(RESOURCE_VALUE_TYPE, "").parse::<u32>().ok()?);
+    let value = match device {
+        Some(device) => table.resolve_for(id, device),
+        None => table.resolve(id),
+    };
 
-    pub fn package_id(&self) -> u8 {
-        ((self.id & 0xff00_0000) >> 24) as u8
-    }
-
-    pub fn type_id(&self) -> u8 {
-        ((self.id & 0x00ff_0000) >> 16) as u8
-    }
-
-    pub fn entry_id(&self) -> u16 {
-        (self.id & 0x0000_ffff) as u16
+    match value.ok()? {
+        EntryValue::Simple(value) => Some(value.to_string()),
+        EntryValue::Complex { .. } => None,
     }
 }
 
-fn find_resource_by_id (table: &arsc::Arsc, key: String) -> Option<String> {
-    let mut str = None;
-    let mut entry = vec![];
-    let id = key.replace("ResourceValueType::Reference/", "").parse::<u32>().unwrap();
-    let res_id = ResourceId::from_u32(id);
-    let p = table.packages.iter().find(|p| p.id == res_id.package_id().into()).unwrap();
-    let t = p.types.iter().find(|t| t.id == res_id.type_id().into()).unwrap();
-    
-    for e in &t.configs {
-        for a in &e.resources.resources {
-            if a.spec_id == res_id.entry_id().into() {
-                entry.push(&a.value);
-            }
-        }
-    }
-    // name_index, data_index, spec_id
-    if !entry.is_empty() {
-        let a = entry.get(0).unwrap();
-        if let arsc::ResourceValue::Plain(b) = &a {
-            match table.global_string_pool.strings.get(b.data_index) {
-                Some(v) => {
-                    str = Some(v.clone())
-                },
-                None => {}
-            }
-        }
-    }
-
-    str
-}
-
-fn parse_resource (buf: Vec<u8>, info: Manifest) -> ExtResult<Manifest> {
+fn parse_resource (buf: Vec<u8>, info: Manifest, device: Option<&DeviceConfig>) -> ExtResult<Manifest> {
     let mut info = info.clone();
     let res = info.check();
-    let cursor = Cursor::new(&buf);
-    let table = arsc::parse_from(cursor)?;
+    let table = ResourceTable::parse(&buf)?;
     for key in res {
-        match find_resource_by_id(&table, info.get(&key)) {
-            Some(v) => {
-                info.set(&key, v);
-            }
-            None => {}
+        if let Some(v) = find_resource_by_id(&table, info.get(&key), device) {
+            info.set(&key, v);
         }
     }
 
@@ -87,10 +37,60 @@ fn parse_resource (buf: Vec<u8>, info: Manifest) -> ExtResult<Manifest> {
 
 pub struct ApkManifest {}
 
+/// What an APK's manifest asks of the device and platform.
+#[derive(Clone, Debug, Default)]
+pub struct ApkDeclarations {
+    /// `<uses-permission>` names, in manifest order.
+    pub permissions: Vec<String>,
+    pub min_sdk: Option<u32>,
+    pub target_sdk: Option<u32>,
+    pub debuggable: bool,
+    /// `android:extractNativeLibs`, which defaults to true.
+    pub extract_native_libs: Option<bool>,
+}
+
+impl ApkDeclarations {
+    /// Reads the declarations from a decoded AndroidManifest.xml.
+    pub fn from_xml(xml: &str) -> ExtResult<ApkDeclarations> {
+        let mut declarations = ApkDeclarations::default();
+        for e in EventReader::from_str(xml) {
+            match e {
+                Ok(XmlEvent::StartElement { name, attributes, .. }) => {
+                    let attr = |key: &str| attributes.iter()
+                        .find(|a| a.name.local_name == key)
+                        .map(|a| a.value.as_str());
+                    match name.local_name.as_str() {
+                        "uses-permission" | "uses-permission-sdk-23" => {
+                            declarations.permissions.extend(attr("name").map(ToString::to_string));
+                        }
+                        "uses-sdk" => {
+                            declarations.min_sdk = attr("minSdkVersion").and_then(axml::int_value);
+                            declarations.target_sdk = attr("targetSdkVersion").and_then(axml::int_value);
+                        }
+                        "application" => {
+                            declarations.debuggable = attr("debuggable").and_then(axml::bool_value) == Some(true);
+                            declarations.extract_native_libs = attr("extractNativeLibs").and_then(axml::bool_value);
+                        }
+                        _ => {}
+                    }
+                }
+                Err(err) => return Err(err.into()),
+                _ => {}
+            }
+        }
+        Ok(declarations)
+    }
+}
+
 impl ApkManifest {
     pub fn from_buffer(buf: Vec<u8>, arsc_buf: Vec<u8>) -> ExtResult<Manifest> {
+        Self::from_buffer_for(buf, arsc_buf, None)
+    }
+
+    /// Parses the manifest, resolving resource references for `device` when given.
+    pub fn from_buffer_for(buf: Vec<u8>, arsc_buf: Vec<u8>, device: Option<&DeviceConfig>) -> ExtResult<Manifest> {
         let mut apk_info = Manifest::default();
-        let str = axml::extract_xml(buf);
+        let str = axml::extract_xml(buf)?;
         let reader = EventReader::from_str(&str);
         for e in reader {
             match e {
@@ -130,19 +130,37 @@ impl ApkManifest {
             }
         }
 
-        parse_resource(arsc_buf, apk_info)
+        parse_resource(arsc_buf, apk_info, device)
     }
 }
 
 pub mod axml {
     use axmldecoder::{Cdata, Element, Node};
+    use crate::error::{Error, ExtResult};
+
+    /// Reads a boolean attribute of a decoded document. axmldecoder writes
+    /// integer-typed values as unsigned decimals, and hex-typed ones as `0x`
+    /// followed by the *decimal* digits, so `0xffffffff` becomes `0x4294967295`.
+    pub fn bool_value(value: &str) -> Option<bool> {
+        match value {
+            "true" | "4294967295" | "0x4294967295" => Some(true),
+            "false" | "0" | "0x0" => Some(false),
+            _ => None,
+        }
+    }
+
+    /// Reads an integer attribute of a decoded document; see [`bool_value`]
+    /// for why digits after `0x` are decimal.
+    pub fn int_value(value: &str) -> Option<u32> {
+        value.strip_prefix("0x").unwrap_or(value).parse().ok()
+    }
 
-    pub fn extract_xml(content: Vec<u8>) -> String {
-        let xml = axmldecoder::parse(content.as_slice()).unwrap();
-        let root = xml.get_root().as_ref().unwrap();
+    pub fn extract_xml(content: Vec<u8>) -> ExtResult<String> {
+        let xml = axmldecoder::parse(content.as_slice())?;
+        let root = xml.get_root().as_ref().ok_or(Error::InvalidFile)?;
         let mut xml_as_string = String::new();
         format_xml(root, 0_usize, &mut xml_as_string);
-        xml_as_string
+        Ok(xml_as_string)
     }
 
     fn format_xml(e: &Node, level: usize, output: &mut String) {
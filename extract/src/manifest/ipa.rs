@@ -1,3 +1,4 @@
+use core::fmt;
 use serde::Deserialize;
 use plist::{Value};
 use crate::{
@@ -19,6 +20,73 @@ pub struct IpaManifest {
     version: String,
     #[serde(rename = "CFBundleVersion")]
     build_number: String,
+    #[serde(flatten)]
+    requirements: IosRequirements,
+}
+
+/// Devices and OS versions an IPA runs on, and the toolchain it was built with.
+#[derive(Deserialize, Clone, Debug, Default)]
+pub struct IosRequirements {
+    #[serde(rename = "MinimumOSVersion")]
+    pub minimum_os_version: Option<String>,
+    #[serde(rename = "UIDeviceFamily", default, deserialize_with = "device_families")]
+    pub device_families: Vec<String>,
+    /// Capabilities the device must have.
+    #[serde(rename = "UIRequiredDeviceCapabilities", default, deserialize_with = "required_capabilities")]
+    pub required_capabilities: Vec<String>,
+    #[serde(rename = "UISupportedInterfaceOrientations", default)]
+    pub orientations: Vec<String>,
+    #[serde(rename = "UISupportedInterfaceOrientations~ipad", default)]
+    pub ipad_orientations: Vec<String>,
+    #[serde(rename = "LSRequiresIPhoneOS")]
+    pub requires_iphone_os: Option<bool>,
+    #[serde(rename = "DTPlatformName")]
+    pub platform_name: Option<String>,
+    #[serde(rename = "DTPlatformVersion")]
+    pub platform_version: Option<String>,
+    #[serde(rename = "DTSDKName")]
+    pub sdk_name: Option<String>,
+    /// Xcode version as `DTXcode` encodes it, e.g. `1520` for 15.2.
+    #[serde(rename = "DTXcode")]
+    pub xcode: Option<String>,
+    #[serde(rename = "BuildMachineOSBuild")]
+    pub build_machine_os_build: Option<String>,
+}
+
+/// `UIDeviceFamily` is usually an array of integers, rarely a single one.
+fn device_families<'de, D: serde::Deserializer<'de>>(deserializer: D) -> Result<Vec<String>, D::Error> {
+    let value = Value::deserialize(deserializer)?;
+    let families = match value {
+        Value::Array(items) => items,
+        other => vec![other],
+    };
+    Ok(families.iter()
+        .filter_map(|f| f.as_unsigned_integer().or_else(|| f.as_string().and_then(|s| s.parse().ok())))
+        .map(|f| match f {
+            1 => "iPhone".to_string(),
+            2 => "iPad".to_string(),
+            3 => "TV".to_string(),
+            4 => "Watch".to_string(),
+            6 => "Mac".to_string(),
+            7 => "Vision".to_string(),
+            other => format!("device family {}", other),
+        })
+        .collect())
+}
+
+/// `UIRequiredDeviceCapabilities` is either an array of capabilities, or a
+/// dictionary where `false` means the device must not have the capability.
+fn required_capabilities<'de, D: serde::Deserializer<'de>>(deserializer: D) -> Result<Vec<String>, D::Error> {
+    Ok(match Value::deserialize(deserializer)? {
+        Value::Array(items) => items.iter()
+            .filter_map(Value::as_string)
+            .map(ToString::to_string)
+            .collect(),
+        Value::Dictionary(dict) => dict.iter()
+            .map(|(k, v)| if v.as_boolean() == Some(false) { format!("!{}", k) } else { k.clone() })
+            .collect(),
+        _ => vec![],
+    })
 }
 
 impl IpaManifest {
@@ -31,6 +99,7 @@ impl IpaManifest {
                     bundle_id,
                     version,
                     build_number,
+                    requirements,
                 } = metadata;
 
                 let icon = find_ipa_icon_path(icon);
@@ -42,6 +111,8 @@ impl IpaManifest {
                         bundle_id,
                         version,
                         build_number,
+                        requirements: Some(requirements),
+                        ..Default::default()
                     }
                 )
             },
@@ -65,4 +136,24 @@ fn find_ipa_icon_path (data: Value) -> String {
     }
 
     str
-}
\ No newline at end of file
+}
+
+impl fmt::Display for IosRequirements {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let unknown = "unknown";
+        writeln!(f, "Minimum OS version: {}", self.minimum_os_version.as_deref().unwrap_or(unknown))?;
+        writeln!(f, "Device families: {}", self.device_families.join(", "))?;
+        writeln!(f, "Required device capabilities: {}", self.required_capabilities.join(", "))?;
+        writeln!(f, "Orientations: {}", self.orientations.join(", "))?;
+        if !self.ipad_orientations.is_empty() {
+            writeln!(f, "iPad orientations: {}", self.ipad_orientations.join(", "))?;
+        }
+        if let Some(requires_iphone_os) = self.requires_iphone_os {
+            writeln!(f, "Requires iPhone OS: {}", requires_iphone_os)?;
+        }
+        writeln!(f, "Platform: {} {}", self.platform_name.as_deref().unwrap_or(unknown), self.platform_version.as_deref().unwrap_or(""))?;
+        writeln!(f, "SDK: {}", self.sdk_name.as_deref().unwrap_or(unknown))?;
+        writeln!(f, "Xcode: {}", self.xcode.as_deref().unwrap_or(unknown))?;
+        writeln!(f, "Build machine OS build: {}", self.build_machine_os_build.as_deref().unwrap_or(unknown))
+    }
+}
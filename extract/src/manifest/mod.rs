@@ -1,7 +1,10 @@
 use core::fmt;
+use crate::signing::ChannelInfo;
 
 mod ipa;
 mod apk;
+mod network;
+mod privacy;
 
 pub const RESOURCE_VALUE_TYPE: &str = "ResourceValueType::Reference/";
 
@@ -13,6 +16,12 @@ pub struct Manifest {
     pub bundle_id: String,
     pub version: String,
     pub build_number: String,
+    /// Walle/VasDolly distribution channel of an APK.
+    pub channel: Option<ChannelInfo>,
+    /// Device, OS and toolchain requirements of an IPA.
+    pub requirements: Option<IosRequirements>,
+    /// Usage descriptions and privacy manifests of an IPA.
+    pub privacy: Option<PrivacyReport>,
 }
 
 impl fmt::Display for Manifest {
@@ -62,5 +71,11 @@ impl Manifest {
     }
 }
 
-pub use ipa::{IPA_EXT, IpaManifest};
-pub use apk::{APK_EXT, ApkManifest};
\ No newline at end of file
+pub use ipa::{IPA_EXT, IpaManifest, IosRequirements};
+pub use apk::{APK_EXT, ApkManifest, ApkDeclarations};
+pub(crate) use apk::{axml, find_resource_by_id};
+pub use network::{NetworkSecurityConfig, DomainConfig, Domain, TrustAnchor, PinSet};
+pub use privacy::{
+    PRIVACY_MANIFEST_NAME, PrivacyReport, PrivacyManifest, UsageDescription,
+    CollectedDataType, AccessedApiType, parse_strings,
+};
\ No newline at end of file
@@ -0,0 +1,229 @@
+use core::fmt;
+use std::collections::HashMap;
+use xml::{EventReader, reader::XmlEvent};
+use crate::error::ExtResult;
+use super::axml;
+
+/// A decoded `res/xml` file referenced by `android:networkSecurityConfig`.
+#[derive(Clone, Debug, Default)]
+pub struct NetworkSecurityConfig {
+    /// Path of the file inside the APK.
+    pub path: String,
+    pub base: Option<DomainConfig>,
+    /// Every `<domain-config>`, nested ones flattened after their parent.
+    pub domains: Vec<DomainConfig>,
+    /// Trust anchors only used while the app is debuggable.
+    pub debug_overrides: Option<DomainConfig>,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct DomainConfig {
+    pub domains: Vec<Domain>,
+    /// `cleartextTrafficPermitted`, inherited by nested domain configs.
+    pub cleartext_permitted: Option<bool>,
+    pub trust_anchors: Vec<TrustAnchor>,
+    pub pin_set: Option<PinSet>,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct Domain {
+    pub name: String,
+    pub include_subdomains: bool,
+}
+
+/// A `<certificates>` source: `system`, `user`, or a bundled certificate file.
+#[derive(Clone, Debug, Default)]
+pub struct TrustAnchor {
+    pub src: String,
+    pub override_pins: bool,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct PinSet {
+    /// `YYYY-MM-DD` after which the pins are no longer enforced.
+    pub expiration: Option<String>,
+    /// Digest algorithm and base64 value of each pinned public key.
+    pub pins: Vec<(String, String)>,
+}
+
+enum Scope {
+    Base,
+    Domain(usize),
+    Debug,
+}
+
+enum Text {
+    Domain,
+    Pin,
+}
+
+impl TrustAnchor {
+    pub fn is_user(&self) -> bool {
+        self.src == "user"
+    }
+
+    pub fn is_bundled(&self) -> bool {
+        self.src != "user" && self.src != "system"
+    }
+}
+
+impl NetworkSecurityConfig {
+    /// The unresolved `android:networkSecurityConfig` attribute of a decoded manifest.
+    pub fn reference_in(manifest_xml: &str) -> ExtResult<Option<String>> {
+        for e in EventReader::from_str(manifest_xml) {
+            match e {
+                Ok(XmlEvent::StartElement { name, attributes, .. }) if name.local_name == "application" => {
+                    return Ok(attributes.into_iter()
+                        .find(|a| a.name.local_name == "networkSecurityConfig")
+                        .map(|a| a.value));
+                }
+                Err(err) => return Err(err.into()),
+                _ => {}
+            }
+        }
+        Ok(None)
+    }
+
+    /// Parses a decoded config, with `resolve` turning resource references
+    /// such as bundled certificate sources into file paths.
+    pub fn parse(path: &str, xml: &str, resolve: impl Fn(&str) -> Option<String>) -> ExtResult<NetworkSecurityConfig> {
+        let mut config = NetworkSecurityConfig {
+            path: path.to_string(),
+            ..Default::default()
+        };
+        let mut scopes: Vec<Scope> = vec![];
+        let mut text: Option<Text> = None;
+        let mut pending: HashMap<String, String> = HashMap::new();
+
+        for e in EventReader::from_str(xml) {
+            match e {
+                Ok(XmlEvent::StartElement { name, attributes, .. }) => {
+                    let attrs: HashMap<String, String> = attributes.into_iter()
+                        .map(|a| (a.name.local_name, a.value))
+                        .collect();
+                    let cleartext = attrs.get("cleartextTrafficPermitted").and_then(|v| axml::bool_value(v));
+                    match name.local_name.as_str() {
+                        "base-config" => {
+                            config.base = Some(DomainConfig { cleartext_permitted: cleartext, ..Default::default() });
+                            scopes.push(Scope::Base);
+                        }
+                        "domain-config" => {
+                            let inherited = match scopes.last() {
+                                Some(Scope::Domain(parent)) => config.domains[*parent].cleartext_permitted,
+                                _ => None,
+                            };
+                            config.domains.push(DomainConfig { cleartext_permitted: cleartext.or(inherited), ..Default::default() });
+                            scopes.push(Scope::Domain(config.domains.len() - 1));
+                        }
+                        "debug-overrides" => {
+                            config.debug_overrides = Some(DomainConfig::default());
+                            scopes.push(Scope::Debug);
+                        }
+                        "domain" => {
+                            text = Some(Text::Domain);
+                            pending = attrs;
+                        }
+                        "pin" => {
+                            text = Some(Text::Pin);
+                            pending = attrs;
+                        }
+                        "certificates" => {
+                            let src = attrs.get("src").cloned().unwrap_or_default();
+                            let anchor = TrustAnchor {
+                                src: resolve(&src).unwrap_or(src),
+                                override_pins: attrs.get("overridePins").and_then(|v| axml::bool_value(v)) == Some(true),
+                            };
+                            if let Some(scope) = config.scope(&scopes) {
+                                scope.trust_anchors.push(anchor);
+                            }
+                        }
+                        "pin-set" => {
+                            if let Some(scope) = config.scope(&scopes) {
+                                scope.pin_set = Some(PinSet { expiration: attrs.get("expiration").cloned(), pins: vec![] });
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+                Ok(XmlEvent::Characters(value)) | Ok(XmlEvent::CData(value)) => {
+                    let value = value.trim().to_string();
+                    let scope = config.scope(&scopes);
+                    match (text.take(), scope) {
+                        (Some(Text::Domain), Some(scope)) => scope.domains.push(Domain {
+                            name: value,
+                            include_subdomains: pending.get("includeSubdomains").and_then(|v| axml::bool_value(v)) == Some(true),
+                        }),
+                        (Some(Text::Pin), Some(scope)) => {
+                            let digest = pending.get("digest").cloned().unwrap_or_else(|| "SHA-256".to_string());
+                            if let Some(pin_set) = scope.pin_set.as_mut() {
+                                pin_set.pins.push((digest, value));
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+                Ok(XmlEvent::EndElement { name }) => match name.local_name.as_str() {
+                    "base-config" | "domain-config" | "debug-overrides" => {
+                        scopes.pop();
+                    }
+                    _ => text = None,
+                },
+                Err(err) => return Err(err.into()),
+                _ => {}
+            }
+        }
+
+        Ok(config)
+    }
+
+    fn scope(&mut self, scopes: &[Scope]) -> Option<&mut DomainConfig> {
+        match scopes.last()? {
+            Scope::Base => self.base.as_mut(),
+            Scope::Domain(i) => self.domains.get_mut(*i),
+            Scope::Debug => self.debug_overrides.as_mut(),
+        }
+    }
+}
+
+impl fmt::Display for NetworkSecurityConfig {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "Network security config: {}", self.path)?;
+        if let Some(base) = &self.base {
+            writeln!(f, "base-config: {}", base)?;
+        }
+        for domain in &self.domains {
+            let names = domain.domains.iter()
+                .map(|d| if d.include_subdomains { format!("*.{}", d.name) } else { d.name.clone() })
+                .collect::<Vec<String>>()
+                .join(", ");
+            writeln!(f, "domain-config {}: {}", names, domain)?;
+        }
+        if let Some(debug) = &self.debug_overrides {
+            writeln!(f, "debug-overrides: {}", debug)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for DomainConfig {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut parts = vec![];
+        if let Some(cleartext) = self.cleartext_permitted {
+            parts.push(format!("cleartext permitted: {}", cleartext));
+        }
+        if !self.trust_anchors.is_empty() {
+            let anchors = self.trust_anchors.iter()
+                .map(|a| if a.override_pins { format!("{} (overrides pins)", a.src) } else { a.src.clone() })
+                .collect::<Vec<String>>()
+                .join(", ");
+            parts.push(format!("trust anchors: {}", anchors));
+        }
+        if let Some(pin_set) = &self.pin_set {
+            parts.push(format!(
+                "{} pin(s), expiring {}",
+                pin_set.pins.len(), pin_set.expiration.as_deref().unwrap_or("never")
+            ));
+        }
+        f.write_str(&parts.join("; "))
+    }
+}
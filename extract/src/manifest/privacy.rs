@@ -0,0 +1,229 @@
+use core::fmt;
+use std::collections::{BTreeMap, BTreeSet};
+use plist::{Dictionary, Value};
+use regex::Regex;
+use lazy_static::lazy_static;
+use crate::error::ExtResult;
+
+lazy_static! {
+    static ref USAGE_DESCRIPTION_KEY: Regex = Regex::new(r"^NS\w+UsageDescription$").unwrap();
+    /// One `"key" = "value";` entry of a text `.strings` file; keys may be unquoted.
+    static ref STRINGS_ENTRY: Regex = Regex::new(r#"(?:"((?:[^"\\]|\\.)*)"|([\w.~-]+))\s*=\s*"((?:[^"\\]|\\.)*)"\s*;"#).unwrap();
+}
+
+pub const PRIVACY_MANIFEST_NAME: &str = "PrivacyInfo.xcprivacy";
+
+/// Privacy usage descriptions and privacy manifests of an IPA.
+#[derive(Clone, Debug, Default)]
+pub struct PrivacyReport {
+    pub usage_descriptions: Vec<UsageDescription>,
+    /// The app's own manifest and those of embedded frameworks and bundles.
+    pub manifests: Vec<PrivacyManifest>,
+}
+
+/// An `NS*UsageDescription` key of the main Info.plist.
+#[derive(Clone, Debug, Default)]
+pub struct UsageDescription {
+    pub key: String,
+    pub text: String,
+    /// Translations from `<lang>.lproj/InfoPlist.strings`, by language.
+    pub localized: BTreeMap<String, String>,
+}
+
+/// A `PrivacyInfo.xcprivacy` file.
+#[derive(Clone, Debug, Default)]
+pub struct PrivacyManifest {
+    /// Path inside the archive.
+    pub path: String,
+    pub tracking: bool,
+    pub tracking_domains: Vec<String>,
+    pub collected_data_types: Vec<CollectedDataType>,
+    pub accessed_api_types: Vec<AccessedApiType>,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct CollectedDataType {
+    pub data_type: String,
+    pub linked: bool,
+    pub tracking: bool,
+    pub purposes: Vec<String>,
+}
+
+/// A required-reason API category, with the approved reasons it is used for.
+#[derive(Clone, Debug, Default)]
+pub struct AccessedApiType {
+    pub api_type: String,
+    pub reasons: Vec<String>,
+}
+
+impl UsageDescription {
+    /// All usage description keys of an Info.plist, without translations.
+    pub fn from_info_plist(plist: &Dictionary) -> Vec<UsageDescription> {
+        plist.iter()
+            .filter(|(k, _)| USAGE_DESCRIPTION_KEY.is_match(k))
+            .map(|(key, text)| UsageDescription {
+                key: key.clone(),
+                text: text.as_string().unwrap_or_default().to_string(),
+                localized: BTreeMap::new(),
+            })
+            .collect()
+    }
+}
+
+impl PrivacyReport {
+    /// Adds the translations of one `InfoPlist.strings` file.
+    pub fn add_localization(&mut self, lang: &str, strings: &BTreeMap<String, String>) {
+        for description in &mut self.usage_descriptions {
+            if let Some(text) = strings.get(&description.key) {
+                description.localized.insert(lang.to_string(), text.clone());
+            }
+        }
+    }
+
+    /// Tracking domains declared by any manifest.
+    pub fn tracking_domains(&self) -> BTreeSet<&str> {
+        self.manifests.iter()
+            .flat_map(|m| m.tracking_domains.iter().map(String::as_str))
+            .collect()
+    }
+
+    /// Collected data types declared by any manifest, with the union of their purposes.
+    pub fn collected_data_types(&self) -> BTreeMap<&str, BTreeSet<&str>> {
+        let mut types: BTreeMap<&str, BTreeSet<&str>> = BTreeMap::new();
+        for data_type in self.manifests.iter().flat_map(|m| &m.collected_data_types) {
+            types.entry(&data_type.data_type)
+                .or_default()
+                .extend(data_type.purposes.iter().map(String::as_str));
+        }
+        types
+    }
+
+    /// Required-reason API categories declared by any manifest, with the union of their reasons.
+    pub fn accessed_api_types(&self) -> BTreeMap<&str, BTreeSet<&str>> {
+        let mut types: BTreeMap<&str, BTreeSet<&str>> = BTreeMap::new();
+        for api in self.manifests.iter().flat_map(|m| &m.accessed_api_types) {
+            types.entry(&api.api_type)
+                .or_default()
+                .extend(api.reasons.iter().map(String::as_str));
+        }
+        types
+    }
+}
+
+impl PrivacyManifest {
+    pub fn from_buffer(path: &str, buf: &[u8]) -> ExtResult<PrivacyManifest> {
+        let plist: Dictionary = plist::from_bytes(buf)?;
+        let strings = |value: Option<&Value>| -> Vec<String> {
+            value.and_then(Value::as_array)
+                .map(|items| items.iter().filter_map(Value::as_string).map(ToString::to_string).collect())
+                .unwrap_or_default()
+        };
+        let dicts = |key: &str| -> Vec<&Dictionary> {
+            plist.get(key)
+                .and_then(Value::as_array)
+                .map(|items| items.iter().filter_map(Value::as_dictionary).collect())
+                .unwrap_or_default()
+        };
+        let string = |dict: &Dictionary, key: &str| dict.get(key)
+            .and_then(Value::as_string)
+            .unwrap_or_default()
+            .to_string();
+        let boolean = |dict: &Dictionary, key: &str| dict.get(key)
+            .and_then(Value::as_boolean)
+            .unwrap_or_default();
+
+        Ok(PrivacyManifest {
+            path: path.to_string(),
+            tracking: boolean(&plist, "NSPrivacyTracking"),
+            tracking_domains: strings(plist.get("NSPrivacyTrackingDomains")),
+            collected_data_types: dicts("NSPrivacyCollectedDataTypes").into_iter()
+                .map(|d| CollectedDataType {
+                    data_type: string(d, "NSPrivacyCollectedDataType"),
+                    linked: boolean(d, "NSPrivacyCollectedDataTypeLinked"),
+                    tracking: boolean(d, "NSPrivacyCollectedDataTypeTracking"),
+                    purposes: strings(d.get("NSPrivacyCollectedDataTypePurposes")),
+                })
+                .collect(),
+            accessed_api_types: dicts("NSPrivacyAccessedAPITypes").into_iter()
+                .map(|d| AccessedApiType {
+                    api_type: string(d, "NSPrivacyAccessedAPIType"),
+                    reasons: strings(d.get("NSPrivacyAccessedAPITypeReasons")),
+                })
+                .collect(),
+        })
+    }
+}
+
+/// Parses a `.strings` file, either compiled to a binary plist or as UTF-8
+/// or UTF-16 text.
+pub fn parse_strings(buf: &[u8]) -> ExtResult<BTreeMap<String, String>> {
+    if buf.starts_with(b"bplist") || buf.starts_with(b"<?xml") {
+        let dict: Dictionary = plist::from_bytes(buf)?;
+        return Ok(dict.iter()
+            .filter_map(|(k, v)| v.as_string().map(|v| (k.clone(), v.to_string())))
+            .collect());
+    }
+
+    let text = match buf {
+        [0xff, 0xfe, rest @ ..] => decode_utf16(rest, u16::from_le_bytes),
+        [0xfe, 0xff, rest @ ..] => decode_utf16(rest, u16::from_be_bytes),
+        _ => String::from_utf8_lossy(buf.strip_prefix(b"\xef\xbb\xbf").unwrap_or(buf)).into_owned(),
+    };
+    Ok(STRINGS_ENTRY.captures_iter(&text)
+        .map(|c| {
+            let key = c.get(1).or_else(|| c.get(2)).map_or("", |m| m.as_str());
+            (unescape(key), unescape(&c[3]))
+        })
+        .collect())
+}
+
+fn decode_utf16(buf: &[u8], from_bytes: fn([u8; 2]) -> u16) -> String {
+    let units: Vec<u16> = buf.chunks_exact(2)
+        .map(|c| from_bytes([c[0], c[1]]))
+        .collect();
+    String::from_utf16_lossy(&units)
+}
+
+fn unescape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => out.push('\n'),
+            Some('t') => out.push('\t'),
+            Some('r') => out.push('\r'),
+            Some(other) => out.push(other),
+            None => {}
+        }
+    }
+    out
+}
+
+impl fmt::Display for PrivacyReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for description in &self.usage_descriptions {
+            writeln!(f, "{}: {}", description.key, description.text)?;
+            for (lang, text) in &description.localized {
+                writeln!(f, "{} ({}): {}", description.key, lang, text)?;
+            }
+        }
+        for manifest in &self.manifests {
+            writeln!(f, "Privacy manifest: {}", manifest.path)?;
+        }
+        writeln!(f, "Tracking: {}", self.manifests.iter().any(|m| m.tracking))?;
+        for domain in self.tracking_domains() {
+            writeln!(f, "Tracking domain: {}", domain)?;
+        }
+        for (data_type, purposes) in self.collected_data_types() {
+            writeln!(f, "Collected data type: {} ({})", data_type, purposes.into_iter().collect::<Vec<&str>>().join(", "))?;
+        }
+        for (api_type, reasons) in self.accessed_api_types() {
+            writeln!(f, "Required reason API: {} ({})", api_type, reasons.into_iter().collect::<Vec<&str>>().join(", "))?;
+        }
+        Ok(())
+    }
+}
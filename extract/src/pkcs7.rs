@@ -0,0 +1,126 @@
+use x509_parser::{certificate::X509Certificate, prelude::FromDer};
+use crate::{
+    der::{DerReader, Tlv, TAG_CONTEXT_0, TAG_CONTEXT_1, TAG_INTEGER, TAG_OCTET_STRING, TAG_OID, TAG_SEQUENCE, TAG_SET},
+    error::{Error, ExtResult},
+};
+
+const OID_SIGNED_DATA: &str = "1.2.840.113549.1.7.2";
+const OID_MESSAGE_DIGEST: &str = "1.2.840.113549.1.9.4";
+
+/// The parts of a PKCS#7 / CMS SignedData we care about.
+pub(crate) struct SignedData<'a> {
+    /// The encapsulated content, absent for detached signatures.
+    pub content: Option<Vec<u8>>,
+    pub certificates: Vec<&'a [u8]>,
+    pub signers: Vec<SignerInfo<'a>>,
+}
+
+pub(crate) struct SignerInfo<'a> {
+    /// DER encoded issuer Name and serial number content of the signing certificate.
+    pub issuer: &'a [u8],
+    pub serial: &'a [u8],
+    pub digest_algorithm: String,
+    /// The `[0] IMPLICIT` signed attributes, as encoded in the block.
+    pub signed_attrs: Option<Tlv<'a>>,
+    pub signature_algorithm: String,
+    pub signature: &'a [u8],
+}
+
+impl<'a> SignedData<'a> {
+    pub fn parse(buf: &'a [u8]) -> ExtResult<SignedData<'a>> {
+        let content_info = DerReader::new(buf).expect(TAG_SEQUENCE)?;
+        let mut content_info = content_info.children();
+        if content_info.expect(TAG_OID)?.oid()? != OID_SIGNED_DATA {
+            return Err(Error::InvalidDer("not a SignedData block".into()));
+        }
+        let signed_data = content_info
+            .expect(TAG_CONTEXT_0)?
+            .children()
+            .expect(TAG_SEQUENCE)?;
+
+        let mut fields = signed_data.children();
+        let _version = fields.expect(TAG_INTEGER)?;
+        let _digest_algorithms = fields.expect(TAG_SET)?;
+        let mut encap_content_info = fields.expect(TAG_SEQUENCE)?.children();
+        let _content_type = encap_content_info.expect(TAG_OID)?;
+        let content = match encap_content_info.optional(TAG_CONTEXT_0)? {
+            Some(explicit) => Some(explicit.children().next()?.octets()?),
+            None => None,
+        };
+
+        let mut certificates = vec![];
+        if let Some(set) = fields.optional(TAG_CONTEXT_0)? {
+            let mut set = set.children();
+            while !set.is_empty() {
+                certificates.push(set.next()?.raw);
+            }
+        }
+        let _crls = fields.optional(TAG_CONTEXT_1)?;
+
+        let mut signers = vec![];
+        let mut infos = fields.expect(TAG_SET)?.children();
+        while !infos.is_empty() {
+            signers.push(SignerInfo::parse(infos.expect(TAG_SEQUENCE)?)?);
+        }
+
+        Ok(SignedData { content, certificates, signers })
+    }
+
+    /// The certificate identified by a signer's issuer and serial number.
+    pub fn signer_certificate(&self, signer: &SignerInfo) -> Option<&'a [u8]> {
+        self.certificates.iter().copied().find(|der| {
+            X509Certificate::from_der(der).is_ok_and(|(_, cert)| {
+                cert.issuer().as_raw() == signer.issuer
+                    && trim_integer(cert.raw_serial()) == trim_integer(signer.serial)
+            })
+        })
+    }
+}
+
+fn trim_integer(value: &[u8]) -> &[u8] {
+    &value[value.iter().take_while(|b| **b == 0).count()..]
+}
+
+impl<'a> SignerInfo<'a> {
+    fn parse(info: Tlv<'a>) -> ExtResult<SignerInfo<'a>> {
+        let mut fields = info.children();
+        let _version = fields.expect(TAG_INTEGER)?;
+        let sid = fields.next()?;
+        // Signers identified by [0] SubjectKeyIdentifier are left unmatched.
+        let (issuer, serial) = if sid.tag == TAG_SEQUENCE {
+            let mut sid = sid.children();
+            (sid.expect(TAG_SEQUENCE)?.raw, sid.expect(TAG_INTEGER)?.content)
+        } else {
+            (&[][..], &[][..])
+        };
+        let digest_algorithm = fields.expect(TAG_SEQUENCE)?.children().expect(TAG_OID)?.oid()?;
+        let signed_attrs = fields.optional(TAG_CONTEXT_0)?;
+        let signature_algorithm = fields.expect(TAG_SEQUENCE)?.children().expect(TAG_OID)?.oid()?;
+        let signature = fields.expect(TAG_OCTET_STRING)?.content;
+
+        Ok(SignerInfo {
+            issuer,
+            serial,
+            digest_algorithm,
+            signed_attrs,
+            signature_algorithm,
+            signature,
+        })
+    }
+
+    /// Value of the `messageDigest` signed attribute.
+    pub fn message_digest(&self) -> ExtResult<Option<&'a [u8]>> {
+        let mut attrs = match self.signed_attrs {
+            Some(attrs) => attrs.children(),
+            None => return Ok(None),
+        };
+        while !attrs.is_empty() {
+            let mut attr = attrs.expect(TAG_SEQUENCE)?.children();
+            if attr.expect(TAG_OID)?.oid()? == OID_MESSAGE_DIGEST {
+                let value = attr.expect(TAG_SET)?.children().expect(TAG_OCTET_STRING)?;
+                return Ok(Some(value.content));
+            }
+        }
+        Ok(None)
+    }
+}
@@ -0,0 +1,193 @@
+use core::fmt;
+use regex::Regex;
+use serde::Deserialize;
+use crate::error::{Error, ExtResult};
+
+/// Release rules for one or more products, read from TOML:
+///
+/// ```toml
+/// [[product]]
+/// bundle_id = "com.example.app"
+/// version_pattern = '^\d+\.\d+\.\d+$'
+/// allowed_permissions = ["android.permission.INTERNET"]
+/// target_sdk = { min = 33 }
+/// signer_sha256 = ["0a1b..."]
+/// max_size = 150_000_000
+/// ```
+#[derive(Deserialize, Clone, Debug, Default)]
+pub struct Policy {
+    #[serde(rename = "product", default)]
+    pub products: Vec<ProductPolicy>,
+}
+
+/// Rules for one product; unset rules are not checked.
+#[derive(Deserialize, Clone, Debug, Default)]
+#[serde(deny_unknown_fields)]
+pub struct ProductPolicy {
+    pub name: Option<String>,
+    /// Selects the product and must match the package.
+    pub bundle_id: String,
+    /// Regex the version name must match.
+    pub version_pattern: Option<String>,
+    /// Lowest allowed build number (`versionCode` or `CFBundleVersion`).
+    pub min_build_number: Option<u64>,
+    /// Android permissions the package may request; others fail the policy.
+    pub allowed_permissions: Option<Vec<String>>,
+    pub min_sdk: Option<Bounds>,
+    pub target_sdk: Option<Bounds>,
+    /// SHA-256 fingerprints of which at least one must have signed the package.
+    pub signer_sha256: Option<Vec<String>>,
+    /// Debuggable APKs, and IPAs with `get-task-allow`, fail unless allowed.
+    #[serde(default)]
+    pub allow_debuggable: bool,
+    /// Largest allowed package size in bytes.
+    pub max_size: Option<u64>,
+    /// `development`, `ad-hoc`, `enterprise` or `app store`, for IPAs.
+    pub provisioning: Option<String>,
+}
+
+#[derive(Deserialize, Clone, Copy, Debug, Default)]
+pub struct Bounds {
+    pub min: Option<u32>,
+    pub max: Option<u32>,
+}
+
+/// What a package declares, gathered for policy checks. Fields that do not
+/// apply to the package type are `None`.
+#[derive(Clone, Debug, Default)]
+pub struct PackageFacts {
+    pub bundle_id: String,
+    pub version: String,
+    pub build_number: String,
+    pub size: u64,
+    pub permissions: Option<Vec<String>>,
+    pub min_sdk: Option<u32>,
+    pub target_sdk: Option<u32>,
+    /// SHA-256 of the signing certificates; for IPAs, of the profile's
+    /// developer certificates, one of which signed the app.
+    pub signer_sha256: Vec<String>,
+    pub debuggable: bool,
+    pub provisioning: Option<String>,
+}
+
+/// Outcome of evaluating a package against a policy.
+#[derive(Clone, Debug, Default)]
+pub struct PolicyReport {
+    /// Name or bundle ID of the product whose rules applied.
+    pub product: Option<String>,
+    pub errors: Vec<String>,
+}
+
+impl PolicyReport {
+    pub fn passed(&self) -> bool {
+        self.errors.is_empty()
+    }
+}
+
+impl Policy {
+    pub fn from_toml(text: &str) -> ExtResult<Policy> {
+        toml::from_str(text).map_err(|err| Error::InvalidPolicy(err.to_string()))
+    }
+
+    /// Checks `facts` against the product with the same bundle ID.
+    pub fn evaluate(&self, facts: &PackageFacts) -> ExtResult<PolicyReport> {
+        match self.products.iter().find(|p| p.bundle_id == facts.bundle_id) {
+            Some(product) => product.evaluate(facts),
+            None => Ok(PolicyReport {
+                product: None,
+                errors: vec![format!("no product in the policy has bundle ID {}", facts.bundle_id)],
+            }),
+        }
+    }
+}
+
+impl ProductPolicy {
+    pub fn evaluate(&self, facts: &PackageFacts) -> ExtResult<PolicyReport> {
+        let mut errors = vec![];
+
+        if let Some(pattern) = &self.version_pattern {
+            let regex = Regex::new(pattern).map_err(|err| Error::InvalidPolicy(err.to_string()))?;
+            if !regex.is_match(&facts.version) {
+                errors.push(format!("version {} does not match {}", facts.version, pattern));
+            }
+        }
+        if let Some(min) = self.min_build_number {
+            match facts.build_number.parse::<u64>() {
+                Ok(build) if build >= min => {}
+                _ => errors.push(format!("build number {} is below {}", facts.build_number, min)),
+            }
+        }
+
+        if let Some(allowed) = &self.allowed_permissions {
+            match &facts.permissions {
+                Some(permissions) => errors.extend(permissions.iter()
+                    .filter(|p| !allowed.contains(p))
+                    .map(|p| format!("permission {} is not allowed", p))),
+                None => errors.push("the package does not declare Android permissions".to_string()),
+            }
+        }
+        check_bounds("minSdkVersion", self.min_sdk, facts.min_sdk, &mut errors);
+        check_bounds("targetSdkVersion", self.target_sdk, facts.target_sdk, &mut errors);
+
+        if let Some(fingerprints) = &self.signer_sha256 {
+            let normalize = |f: &str| f.replace(':', "").to_lowercase();
+            let signed = facts.signer_sha256.iter()
+                .any(|s| fingerprints.iter().any(|f| normalize(f) == normalize(s)));
+            if !signed {
+                errors.push("not signed by a required certificate".to_string());
+            }
+        }
+        if facts.debuggable && !self.allow_debuggable {
+            errors.push("the package is debuggable".to_string());
+        }
+        if let Some(max) = self.max_size {
+            if facts.size > max {
+                errors.push(format!("size {} bytes exceeds {} bytes", facts.size, max));
+            }
+        }
+        if let Some(expected) = &self.provisioning {
+            match &facts.provisioning {
+                Some(provisioning) if provisioning.eq_ignore_ascii_case(expected) => {}
+                Some(provisioning) => errors.push(format!("provisioned for {}, expected {}", provisioning, expected)),
+                None => errors.push("the package has no provisioning profile".to_string()),
+            }
+        }
+
+        Ok(PolicyReport {
+            product: Some(self.name.clone().unwrap_or_else(|| self.bundle_id.clone())),
+            errors,
+        })
+    }
+}
+
+fn check_bounds(name: &str, bounds: Option<Bounds>, value: Option<u32>, errors: &mut Vec<String>) {
+    let bounds = match bounds {
+        Some(bounds) => bounds,
+        None => return,
+    };
+    let value = match value {
+        Some(value) => value,
+        None => return errors.push(format!("{} is not declared", name)),
+    };
+    if bounds.min.is_some_and(|min| value < min) || bounds.max.is_some_and(|max| value > max) {
+        errors.push(format!(
+            "{} {} is outside {}..={}",
+            name, value,
+            bounds.min.map_or(String::new(), |v| v.to_string()),
+            bounds.max.map_or(String::new(), |v| v.to_string()),
+        ));
+    }
+}
+
+impl fmt::Display for PolicyReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let Some(product) = &self.product {
+            writeln!(f, "Policy for {}", product)?;
+        }
+        writeln!(f, "Policy passed: {}", self.passed())?;
+        for err in &self.errors {
+            writeln!(f, "ERROR: {}", err)?;
+        }
+        Ok(())
+    }
+}
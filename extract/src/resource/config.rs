@@ -0,0 +1,279 @@
+use core::fmt;
+use crate::{
+    bytes::ByteReader,
+    error::{ExtResult},
+};
+
+/// Size of the largest `ResTable_config` we know how to read.
+const CONFIG_SIZE: usize = 64;
+
+pub const DENSITY_ANY: u16 = 0xfffe;
+pub const DENSITY_NONE: u16 = 0xffff;
+
+/// A decoded `ResTable_config`, the qualifiers a resource value applies to.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct ResConfig {
+    pub mcc: u16,
+    pub mnc: u16,
+    pub language: [u8; 2],
+    pub country: [u8; 2],
+    pub orientation: u8,
+    pub touchscreen: u8,
+    pub density: u16,
+    pub keyboard: u8,
+    pub navigation: u8,
+    pub input_flags: u8,
+    pub grammatical_inflection: u8,
+    pub screen_width: u16,
+    pub screen_height: u16,
+    pub sdk_version: u16,
+    pub minor_version: u16,
+    pub screen_layout: u8,
+    pub ui_mode: u8,
+    pub smallest_screen_width_dp: u16,
+    pub screen_width_dp: u16,
+    pub screen_height_dp: u16,
+    pub locale_script: [u8; 4],
+    pub locale_variant: [u8; 8],
+    pub screen_layout2: u8,
+    pub color_mode: u8,
+    pub locale_numbering_system: [u8; 8],
+}
+
+impl ResConfig {
+    /// Reads a config at `pos`; fields beyond its declared size stay zero.
+    pub fn parse(buf: &[u8], pos: usize) -> ExtResult<ResConfig> {
+        let size = ByteReader::at(buf, pos).u32()? as usize;
+        let mut raw = [0u8; CONFIG_SIZE];
+        let len = size.clamp(4, CONFIG_SIZE);
+        raw[..len].copy_from_slice(ByteReader::at(buf, pos).bytes(len)?);
+
+        let mut r = ByteReader::at(&raw, 4);
+        let mut config = ResConfig {
+            mcc: r.u16()?,
+            mnc: r.u16()?,
+            ..ResConfig::default()
+        };
+        config.language.copy_from_slice(r.bytes(2)?);
+        config.country.copy_from_slice(r.bytes(2)?);
+        config.orientation = r.u8()?;
+        config.touchscreen = r.u8()?;
+        config.density = r.u16()?;
+        config.keyboard = r.u8()?;
+        config.navigation = r.u8()?;
+        config.input_flags = r.u8()?;
+        config.grammatical_inflection = r.u8()?;
+        config.screen_width = r.u16()?;
+        config.screen_height = r.u16()?;
+        config.sdk_version = r.u16()?;
+        config.minor_version = r.u16()?;
+        config.screen_layout = r.u8()?;
+        config.ui_mode = r.u8()?;
+        config.smallest_screen_width_dp = r.u16()?;
+        config.screen_width_dp = r.u16()?;
+        config.screen_height_dp = r.u16()?;
+        config.locale_script.copy_from_slice(r.bytes(4)?);
+        config.locale_variant.copy_from_slice(r.bytes(8)?);
+        config.screen_layout2 = r.u8()?;
+        config.color_mode = r.u8()?;
+        r.skip(2)?;
+        // localeScriptWasComputed
+        r.skip(1)?;
+        config.locale_numbering_system.copy_from_slice(r.bytes(8)?);
+
+        Ok(config)
+    }
+
+    pub fn is_default(&self) -> bool {
+        *self == ResConfig::default()
+    }
+
+    pub fn language(&self) -> String {
+        unpack_language_or_region(&self.language, b'a')
+    }
+
+    pub fn region(&self) -> String {
+        unpack_language_or_region(&self.country, b'0')
+    }
+
+    pub fn script(&self) -> String {
+        trim_nul(&self.locale_script)
+    }
+
+    pub fn variant(&self) -> String {
+        trim_nul(&self.locale_variant)
+    }
+
+    /// BCP 47 style locale, e.g. `de-DE` or `sr-Latn-RS`; empty for no locale.
+    pub fn locale(&self) -> String {
+        [self.language(), self.script(), self.region(), self.variant()]
+            .into_iter()
+            .filter(|s| !s.is_empty())
+            .collect::<Vec<String>>()
+            .join("-")
+    }
+
+    fn qualifiers(&self) -> Vec<String> {
+        let mut out: Vec<String> = vec![];
+
+        if self.mcc != 0 {
+            out.push(format!("mcc{}", self.mcc));
+        }
+        if self.mnc != 0 {
+            out.push(format!("mnc{}", self.mnc));
+        }
+        let language = self.language();
+        if !language.is_empty() {
+            let region = self.region();
+            if self.script().is_empty() && self.variant().is_empty() && language.len() == 2 {
+                if region.is_empty() {
+                    out.push(language);
+                } else {
+                    out.push(format!("{}-r{}", language, region));
+                }
+            } else {
+                out.push(format!("b+{}", self.locale().replace('-', "+")));
+            }
+        }
+        match self.screen_layout & 0xc0 {
+            0x40 => out.push("ldltr".into()),
+            0x80 => out.push("ldrtl".into()),
+            _ => {}
+        }
+        if self.smallest_screen_width_dp != 0 {
+            out.push(format!("sw{}dp", self.smallest_screen_width_dp));
+        }
+        if self.screen_width_dp != 0 {
+            out.push(format!("w{}dp", self.screen_width_dp));
+        }
+        if self.screen_height_dp != 0 {
+            out.push(format!("h{}dp", self.screen_height_dp));
+        }
+        match self.screen_layout & 0x0f {
+            1 => out.push("small".into()),
+            2 => out.push("normal".into()),
+            3 => out.push("large".into()),
+            4 => out.push("xlarge".into()),
+            _ => {}
+        }
+        match self.screen_layout & 0x30 {
+            0x10 => out.push("notlong".into()),
+            0x20 => out.push("long".into()),
+            _ => {}
+        }
+        match self.screen_layout2 & 0x03 {
+            1 => out.push("notround".into()),
+            2 => out.push("round".into()),
+            _ => {}
+        }
+        match self.color_mode & 0x03 {
+            1 => out.push("nowidecg".into()),
+            2 => out.push("widecg".into()),
+            _ => {}
+        }
+        match self.color_mode & 0x0c {
+            0x04 => out.push("lowdr".into()),
+            0x08 => out.push("highdr".into()),
+            _ => {}
+        }
+        match self.orientation {
+            1 => out.push("port".into()),
+            2 => out.push("land".into()),
+            3 => out.push("square".into()),
+            _ => {}
+        }
+        match self.ui_mode & 0x0f {
+            2 => out.push("desk".into()),
+            3 => out.push("car".into()),
+            4 => out.push("television".into()),
+            5 => out.push("appliance".into()),
+            6 => out.push("watch".into()),
+            7 => out.push("vrheadset".into()),
+            _ => {}
+        }
+        match self.ui_mode & 0x30 {
+            0x10 => out.push("notnight".into()),
+            0x20 => out.push("night".into()),
+            _ => {}
+        }
+        match self.density {
+            0 => {}
+            120 => out.push("ldpi".into()),
+            160 => out.push("mdpi".into()),
+            213 => out.push("tvdpi".into()),
+            240 => out.push("hdpi".into()),
+            320 => out.push("xhdpi".into()),
+            480 => out.push("xxhdpi".into()),
+            640 => out.push("xxxhdpi".into()),
+            DENSITY_ANY => out.push("anydpi".into()),
+            DENSITY_NONE => out.push("nodpi".into()),
+            d => out.push(format!("{}dpi", d)),
+        }
+        match self.touchscreen {
+            1 => out.push("notouch".into()),
+            2 => out.push("stylus".into()),
+            3 => out.push("finger".into()),
+            _ => {}
+        }
+        match self.input_flags & 0x03 {
+            1 => out.push("keysexposed".into()),
+            2 => out.push("keyshidden".into()),
+            3 => out.push("keyssoft".into()),
+            _ => {}
+        }
+        match self.keyboard {
+            1 => out.push("nokeys".into()),
+            2 => out.push("qwerty".into()),
+            3 => out.push("12key".into()),
+            _ => {}
+        }
+        match self.input_flags & 0x0c {
+            0x04 => out.push("navexposed".into()),
+            0x08 => out.push("navhidden".into()),
+            _ => {}
+        }
+        match self.navigation {
+            1 => out.push("nonav".into()),
+            2 => out.push("dpad".into()),
+            3 => out.push("trackball".into()),
+            4 => out.push("wheel".into()),
+            _ => {}
+        }
+        if self.screen_width != 0 && self.screen_height != 0 {
+            out.push(format!("{}x{}", self.screen_width, self.screen_height));
+        }
+        if self.sdk_version != 0 {
+            out.push(format!("v{}", self.sdk_version));
+        }
+
+        out
+    }
+}
+
+/// Prints the qualifier string aapt uses for resource directories, e.g. `de-rDE-xxhdpi-v26`.
+impl fmt::Display for ResConfig {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.qualifiers().join("-"))
+    }
+}
+
+fn unpack_language_or_region(packed: &[u8; 2], base: u8) -> String {
+    if packed[0] & 0x80 != 0 {
+        let first = packed[1] & 0x1f;
+        let second = ((packed[1] & 0xe0) >> 5) + ((packed[0] & 0x03) << 3);
+        let third = (packed[0] & 0x7c) >> 2;
+        [first + base, second + base, third + base]
+            .iter()
+            .map(|c| *c as char)
+            .collect()
+    } else {
+        trim_nul(packed)
+    }
+}
+
+fn trim_nul(raw: &[u8]) -> String {
+    raw.iter()
+        .take_while(|c| **c != 0)
+        .map(|c| *c as char)
+        .collect()
+}
@@ -0,0 +1,240 @@
+use std::str::FromStr;
+use crate::error::{Error, ExtResult};
+use super::{ResConfig, ResourceId, ResourceTable, Entry, EntryValue};
+use super::config::DENSITY_ANY;
+
+const DENSITY_MEDIUM: u16 = 160;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ScreenSize {
+    Small = 1,
+    Normal = 2,
+    Large = 3,
+    XLarge = 4,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Orientation {
+    Portrait = 1,
+    Landscape = 2,
+}
+
+/// The device a resource lookup is made for. Without a language or region,
+/// configs qualified on one never match, as on a device. Other unset fields are
+/// unknown: configs qualified on them still match and are not preferred for it,
+/// except density, where an unset value ranks configs like an mdpi device
+/// would. Qualifiers not modelled here (mcc/mnc, ui mode type, input methods,
+/// ...) are assumed to be those of a plain phone and configs requiring anything
+/// else are skipped.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct DeviceConfig {
+    pub language: Option<String>,
+    pub region: Option<String>,
+    pub script: Option<String>,
+    pub density: Option<u16>,
+    pub sdk_version: Option<u16>,
+    pub night: Option<bool>,
+    pub smallest_width_dp: Option<u16>,
+    pub width_dp: Option<u16>,
+    pub height_dp: Option<u16>,
+    pub screen_size: Option<ScreenSize>,
+    pub orientation: Option<Orientation>,
+}
+
+/// Parses aapt style qualifiers, e.g. `de-rDE-xxhdpi-v34` or `de-DE-night-sw600dp`.
+impl FromStr for DeviceConfig {
+    type Err = Error;
+
+    fn from_str(s: &str) -> ExtResult<DeviceConfig> {
+        let mut device = DeviceConfig::default();
+        let invalid = |q: &str| Error::InvalidDeviceConfig(q.to_string());
+        let number = |q: &str, prefix: &str, suffix: &str| -> Option<u16> {
+            q.strip_prefix(prefix)?.strip_suffix(suffix)?.parse().ok()
+        };
+
+        for q in s.split('-').filter(|q| !q.is_empty()) {
+            if let Some(locale) = q.strip_prefix("b+") {
+                let mut parts = locale.split('+');
+                device.language = parts.next().map(str::to_lowercase);
+                for part in parts {
+                    if part.len() == 4 {
+                        device.script = Some(part.to_string());
+                    } else {
+                        device.region = Some(part.to_uppercase());
+                    }
+                }
+                continue;
+            }
+
+            match q {
+                "ldpi" => device.density = Some(120),
+                "mdpi" => device.density = Some(160),
+                "tvdpi" => device.density = Some(213),
+                "hdpi" => device.density = Some(240),
+                "xhdpi" => device.density = Some(320),
+                "xxhdpi" => device.density = Some(480),
+                "xxxhdpi" => device.density = Some(640),
+                "night" => device.night = Some(true),
+                "notnight" => device.night = Some(false),
+                "small" => device.screen_size = Some(ScreenSize::Small),
+                "normal" => device.screen_size = Some(ScreenSize::Normal),
+                "large" => device.screen_size = Some(ScreenSize::Large),
+                "xlarge" => device.screen_size = Some(ScreenSize::XLarge),
+                "port" => device.orientation = Some(Orientation::Portrait),
+                "land" => device.orientation = Some(Orientation::Landscape),
+                _ if number(q, "sw", "dp").is_some() => device.smallest_width_dp = number(q, "sw", "dp"),
+                _ if number(q, "w", "dp").is_some() => device.width_dp = number(q, "w", "dp"),
+                _ if number(q, "h", "dp").is_some() => device.height_dp = number(q, "h", "dp"),
+                _ if number(q, "", "dpi").is_some() => device.density = number(q, "", "dpi"),
+                _ if number(q, "v", "").is_some() => device.sdk_version = number(q, "v", ""),
+                _ if q.len() == 3 && q.starts_with('r') && q[1..].chars().all(|c| c.is_ascii_uppercase()) => {
+                    device.region = Some(q[1..].to_string());
+                }
+                _ if q.len() == 2 && q.chars().all(|c| c.is_ascii_uppercase()) => {
+                    device.region = Some(q.to_string());
+                }
+                _ if (2..=3).contains(&q.len()) && q.chars().all(|c| c.is_ascii_lowercase()) => {
+                    device.language = Some(q.to_string());
+                }
+                _ => return Err(invalid(q)),
+            }
+        }
+
+        Ok(device)
+    }
+}
+
+impl ResConfig {
+    /// Whether a device would consider this config at all.
+    pub fn matches(&self, device: &DeviceConfig) -> bool {
+        if self.mcc != 0 || self.mnc != 0 {
+            return false;
+        }
+
+        let language = self.language();
+        if !language.is_empty() {
+            if device.language.as_deref() != Some(language.as_str()) {
+                return false;
+            }
+            let region = self.region();
+            if !region.is_empty() && device.region.as_deref() != Some(region.as_str()) {
+                return false;
+            }
+            let script = self.script();
+            if !script.is_empty() && device.script.as_ref().is_some_and(|s| *s != script) {
+                return false;
+            }
+        }
+
+        let fits = |required: u16, actual: Option<u16>| required == 0 || !matches!(actual, Some(a) if required > a);
+        if !fits(self.smallest_screen_width_dp, device.smallest_width_dp)
+            || !fits(self.screen_width_dp, device.width_dp)
+            || !fits(self.screen_height_dp, device.height_dp)
+            || !fits((self.screen_layout & 0x0f) as u16, device.screen_size.map(|s| s as u16))
+            || !fits(self.sdk_version, device.sdk_version)
+        {
+            return false;
+        }
+
+        let orientation = self.orientation as u16;
+        if orientation != 0 && device.orientation.is_some_and(|o| o as u16 != orientation) {
+            return false;
+        }
+
+        // Only a normal (phone) ui mode type is modelled.
+        if self.ui_mode & 0x0f > 1 {
+            return false;
+        }
+        match self.ui_mode & 0x30 {
+            0x10 if device.night == Some(true) => return false,
+            0x20 if device.night == Some(false) => return false,
+            _ => {}
+        }
+
+        self.touchscreen == 0 && self.keyboard == 0 && self.navigation == 0 && self.input_flags == 0
+            && self.screen_width == 0 && self.screen_height == 0
+    }
+
+    /// Android's `ResTable_config::isBetterThan`: both configs are assumed to
+    /// match `device`, qualifiers are compared in precedence order. Simpler than
+    /// AOSP: width and height dp prefer the larger value on their own rather
+    /// than the one closest to the device, and the screenLayout long/round
+    /// fixups are skipped.
+    pub fn is_better_than(&self, other: &ResConfig, device: &DeviceConfig) -> bool {
+        let language = (!self.language().is_empty(), !self.region().is_empty(), !self.script().is_empty());
+        let other_language = (!other.language().is_empty(), !other.region().is_empty(), !other.script().is_empty());
+        if language != other_language {
+            return language > other_language;
+        }
+
+        let larger = |a: u16, b: u16, requested: bool| if requested && a != b { Some(a > b) } else { None };
+        let checks = [
+            larger(self.smallest_screen_width_dp, other.smallest_screen_width_dp, device.smallest_width_dp.is_some()),
+            larger(self.screen_width_dp, other.screen_width_dp, device.width_dp.is_some()),
+            larger(self.screen_height_dp, other.screen_height_dp, device.height_dp.is_some()),
+            larger((self.screen_layout & 0x0f) as u16, (other.screen_layout & 0x0f) as u16, device.screen_size.is_some()),
+            larger(self.orientation as u16, other.orientation as u16, device.orientation.is_some()),
+            larger((self.ui_mode & 0x30) as u16, (other.ui_mode & 0x30) as u16, device.night.is_some()),
+        ];
+        if let Some(better) = checks.into_iter().flatten().next() {
+            return better;
+        }
+
+        if self.density != other.density {
+            return is_density_better(self.density, other.density, device.density.unwrap_or(0));
+        }
+
+        if self.sdk_version != other.sdk_version {
+            return self.sdk_version > other.sdk_version;
+        }
+
+        false
+    }
+}
+
+/// The density part of `isBetterThan`, ported as written: unset densities
+/// count as mdpi, and nodpi (0xffff) takes part in the scaling formula.
+fn is_density_better(this: u16, other: u16, requested: u16) -> bool {
+    let this = if this == 0 { DENSITY_MEDIUM } else { this };
+    let other = if other == 0 { DENSITY_MEDIUM } else { other };
+
+    // anydpi always beats scaling a bucket.
+    if this == DENSITY_ANY {
+        return true;
+    } else if other == DENSITY_ANY {
+        return false;
+    }
+    let requested = if requested == 0 || requested == DENSITY_ANY { DENSITY_MEDIUM } else { requested };
+    let (this, other, requested) = (this as i64, other as i64, requested as i64);
+
+    let (h, l, this_bigger) = if this >= other { (this, other, true) } else { (other, this, false) };
+    if requested >= h {
+        return this_bigger;
+    }
+    if l >= requested {
+        return !this_bigger;
+    }
+    // Scaling down is considered twice as good as scaling up.
+    if (2 * l - requested) * h > requested * requested {
+        !this_bigger
+    } else {
+        this_bigger
+    }
+}
+
+impl ResourceTable {
+    /// The entry `device` would load for `id`, following Android's resource selection.
+    pub fn best_entry(&self, id: ResourceId, device: &DeviceConfig) -> Option<(&ResConfig, &Entry)> {
+        self.entries(id)
+            .into_iter()
+            .filter(|(config, _)| config.matches(device))
+            .reduce(|best, candidate| {
+                if candidate.0.is_better_than(best.0, device) { candidate } else { best }
+            })
+    }
+
+    /// Like [`ResourceTable::resolve`], picking every entry along the way for `device`.
+    pub fn resolve_for(&self, id: ResourceId, device: &DeviceConfig) -> ExtResult<EntryValue> {
+        self.resolve_with(id, &|table, id| table.best_entry(id, device).map(|(_, e)| e))
+    }
+}
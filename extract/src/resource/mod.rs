@@ -0,0 +1,51 @@
+use core::fmt;
+
+mod config;
+mod device;
+mod resolve;
+mod table;
+mod value;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ResourceId {
+    id: u32,
+}
+
+impl ResourceId {
+    pub fn from_parts(package_id: u8, type_id: u8, entry_id: u16) -> ResourceId {
+        ResourceId {
+            id: ((package_id as u32) << 24) | ((type_id as u32) << 16) | entry_id as u32,
+        }
+    }
+
+    pub fn from_u32(id: u32) -> ResourceId {
+        ResourceId { id }
+    }
+
+    pub fn to_u32(&self) -> u32 {
+        self.id
+    }
+
+    pub fn package_id(&self) -> u8 {
+        ((self.id & 0xff00_0000) >> 24) as u8
+    }
+
+    pub fn type_id(&self) -> u8 {
+        ((self.id & 0x00ff_0000) >> 16) as u8
+    }
+
+    pub fn entry_id(&self) -> u16 {
+        (self.id & 0x0000_ffff) as u16
+    }
+}
+
+impl fmt::Display for ResourceId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "0x{:08x}", self.id)
+    }
+}
+
+pub use config::ResConfig;
+pub use device::{DeviceConfig, ScreenSize, Orientation};
+pub use table::{ResourceTable, StringPool, Package, ResType, TypeConfig, Entry, EntryValue};
+pub use value::{ResValue, DimensionUnit, FractionUnit};
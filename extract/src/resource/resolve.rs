@@ -0,0 +1,121 @@
+use core::cmp::Reverse;
+use std::collections::HashSet;
+use crate::error::{Error, ExtResult};
+use super::{
+    config::{DENSITY_ANY, DENSITY_NONE},
+    ResConfig, ResValue, ResourceId, ResourceTable, Entry, EntryValue,
+};
+
+impl ResourceTable {
+    /// Picks the entry used when no device configuration is given: entries
+    /// without a density first, then concrete densities from the highest, then
+    /// `anydpi` and `nodpi`, so launcher icons resolve to a bitmap rather than
+    /// an adaptive icon. Within each group the least qualified config wins,
+    /// ties broken by qualifier string so the choice does not depend on table order.
+    pub fn default_entry(&self, id: ResourceId) -> Option<(&ResConfig, &Entry)> {
+        let mut entries = self.entries(id);
+        entries.sort_by_cached_key(|(config, _)| {
+            let density_group = match config.density {
+                0 => 0,
+                DENSITY_ANY | DENSITY_NONE => 2,
+                _ => 1,
+            };
+            let qualifiers = config.to_string();
+            (density_group, Reverse(config.density), qualifiers.split('-').filter(|q| !q.is_empty()).count(), qualifiers)
+        });
+        entries.into_iter().next()
+    }
+
+    /// Resolves `id` to its final value, following `@ref` chains. Bags come
+    /// back flattened: parent styles merged in, items resolved and `parent`
+    /// left empty.
+    pub fn resolve(&self, id: ResourceId) -> ExtResult<EntryValue> {
+        self.resolve_with(id, &|table, id| table.default_entry(id).map(|(_, e)| e))
+    }
+
+    /// Resolves a value that may itself be a reference.
+    pub fn resolve_value(&self, value: &ResValue) -> ExtResult<EntryValue> {
+        match value.reference() {
+            Some(id) => self.resolve(id),
+            None => Ok(EntryValue::Simple(value.clone())),
+        }
+    }
+
+    pub(crate) fn resolve_with<'a, F>(&'a self, id: ResourceId, pick: &F) -> ExtResult<EntryValue>
+    where
+        F: Fn(&'a ResourceTable, ResourceId) -> Option<&'a Entry>,
+    {
+        match self.follow(id, pick)? {
+            (_, EntryValue::Simple(value)) => Ok(EntryValue::Simple(value.clone())),
+            (bag, EntryValue::Complex { .. }) => self.resolve_bag(bag, pick),
+        }
+    }
+
+    /// Walks the reference chain starting at `id` up to the first entry that
+    /// is not a reference, returning that entry's id and value.
+    fn follow<'a, F>(&'a self, id: ResourceId, pick: &F) -> ExtResult<(ResourceId, &'a EntryValue)>
+    where
+        F: Fn(&'a ResourceTable, ResourceId) -> Option<&'a Entry>,
+    {
+        let mut visited = HashSet::new();
+        let mut current = id;
+
+        loop {
+            if !visited.insert(current) {
+                return Err(Error::ResourceCycle(id));
+            }
+            let entry = pick(self, current).ok_or(Error::ResourceNotFound(current))?;
+
+            match &entry.value {
+                EntryValue::Simple(value) if value.reference().is_some() => {
+                    current = value.reference().unwrap();
+                }
+                value => return Ok((current, value)),
+            }
+        }
+    }
+
+    fn resolve_bag<'a, F>(&'a self, id: ResourceId, pick: &F) -> ExtResult<EntryValue>
+    where
+        F: Fn(&'a ResourceTable, ResourceId) -> Option<&'a Entry>,
+    {
+        let mut visited = HashSet::new();
+        let mut chain: Vec<&Vec<(ResourceId, ResValue)>> = vec![];
+        let mut current = Some(id);
+
+        while let Some(bag) = current {
+            if !visited.insert(bag) {
+                return Err(Error::ResourceCycle(id));
+            }
+            match pick(self, bag).map(|e| &e.value) {
+                Some(EntryValue::Complex { parent, values }) => {
+                    chain.push(values);
+                    current = *parent;
+                }
+                // Parents from other packages (e.g. android:style/Theme) are not in this table.
+                _ => break,
+            }
+        }
+
+        // Apply the chain root first so children override inherited items.
+        let mut values: Vec<(ResourceId, ResValue)> = vec![];
+        for items in chain.into_iter().rev() {
+            for (key, value) in items {
+                // Items pointing at other bags are left as references rather than expanded.
+                let value = match value.reference().map(|target| self.follow(target, pick)) {
+                    Some(Ok((_, EntryValue::Simple(resolved)))) => resolved.clone(),
+                    _ => value.clone(),
+                };
+                match values.iter_mut().find(|(k, _)| k == key) {
+                    Some(slot) => slot.1 = value,
+                    None => values.push((*key, value)),
+                }
+            }
+        }
+
+        Ok(EntryValue::Complex {
+            parent: None,
+            values,
+        })
+    }
+}
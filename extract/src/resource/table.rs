@@ -0,0 +1,467 @@
+use core::fmt;
+use crate::{
+    bytes::ByteReader,
+    error::{Error, ExtResult},
+};
+use super::{ResConfig, ResValue, ResourceId};
+
+const RES_STRING_POOL_TYPE: u16 = 0x0001;
+const RES_TABLE_TYPE: u16 = 0x0002;
+const RES_TABLE_PACKAGE_TYPE: u16 = 0x0200;
+const RES_TABLE_TYPE_TYPE: u16 = 0x0201;
+const RES_TABLE_TYPE_SPEC_TYPE: u16 = 0x0202;
+
+const STRING_POOL_UTF8_FLAG: u32 = 0x100;
+
+const TYPE_FLAG_SPARSE: u8 = 0x01;
+const TYPE_FLAG_OFFSET16: u8 = 0x02;
+const NO_ENTRY: u32 = 0xffff_ffff;
+const NO_ENTRY16: u16 = 0xffff;
+
+const ENTRY_FLAG_COMPLEX: u16 = 0x0001;
+const ENTRY_FLAG_PUBLIC: u16 = 0x0002;
+const ENTRY_FLAG_COMPACT: u16 = 0x0008;
+
+struct Chunk {
+    kind: u16,
+    start: usize,
+    header_size: usize,
+    size: usize,
+}
+
+impl Chunk {
+    fn read(buf: &[u8], start: usize) -> ExtResult<Chunk> {
+        let mut r = ByteReader::at(buf, start);
+        let kind = r.u16()?;
+        let header_size = r.u16()? as usize;
+        let size = r.u32()? as usize;
+
+        if header_size < 8 || size < header_size || start + size > buf.len() {
+            return Err(Error::InvalidResourceTable(format!("bad chunk at offset {}", start)));
+        }
+
+        Ok(Chunk { kind, start, header_size, size })
+    }
+
+    fn body(&self) -> usize {
+        self.start + self.header_size
+    }
+
+    fn end(&self) -> usize {
+        self.start + self.size
+    }
+
+    /// Iterates over the chunks nested after this chunk's header.
+    fn children<'a>(&self, buf: &'a [u8]) -> impl Iterator<Item = ExtResult<Chunk>> + 'a {
+        let end = self.end();
+        let mut pos = self.body();
+        std::iter::from_fn(move || {
+            if pos + 8 > end {
+                return None;
+            }
+            let chunk = Chunk::read(buf, pos);
+            pos = match &chunk {
+                Ok(c) => pos + c.size,
+                Err(_) => end,
+            };
+            Some(chunk)
+        })
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct StringPool {
+    pub strings: Vec<String>,
+    pub utf8: bool,
+}
+
+impl StringPool {
+    fn parse(buf: &[u8], chunk: &Chunk) -> ExtResult<StringPool> {
+        let mut r = ByteReader::at(buf, chunk.start + 8);
+        let count = r.u32()? as usize;
+        let _style_count = r.u32()?;
+        let flags = r.u32()?;
+        let strings_start = chunk.start + r.u32()? as usize;
+        let utf8 = flags & STRING_POOL_UTF8_FLAG != 0;
+
+        let data = &buf[..chunk.end()];
+        r.seek(chunk.body())?;
+        let mut strings = Vec::with_capacity(count.min(r.remaining() / 4));
+        for _ in 0..count {
+            let offset = r.u32()? as usize;
+            strings.push(read_pool_string(data, strings_start + offset, utf8)?);
+        }
+
+        Ok(StringPool { strings, utf8 })
+    }
+
+    pub fn get(&self, index: usize) -> Option<&str> {
+        self.strings.get(index).map(String::as_str)
+    }
+}
+
+fn read_pool_string(buf: &[u8], pos: usize, utf8: bool) -> ExtResult<String> {
+    let mut r = ByteReader::at(buf, pos);
+    if utf8 {
+        let _chars = read_utf8_len(&mut r)?;
+        let len = read_utf8_len(&mut r)?;
+        Ok(String::from_utf8_lossy(r.bytes(len)?).into_owned())
+    } else {
+        let mut len = r.u16()? as usize;
+        if len & 0x8000 != 0 {
+            len = ((len & 0x7fff) << 16) | r.u16()? as usize;
+        }
+        let units = (0..len)
+            .map(|_| r.u16())
+            .collect::<ExtResult<Vec<u16>>>()?;
+        Ok(String::from_utf16_lossy(&units))
+    }
+}
+
+fn read_utf8_len(r: &mut ByteReader) -> ExtResult<usize> {
+    let len = r.u8()? as usize;
+    if len & 0x80 != 0 {
+        Ok(((len & 0x7f) << 8) | r.u8()? as usize)
+    } else {
+        Ok(len)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum EntryValue {
+    Simple(ResValue),
+    /// A bag (style, array, plurals, ...): parent style and `(attribute, value)` pairs.
+    Complex {
+        parent: Option<ResourceId>,
+        values: Vec<(ResourceId, ResValue)>,
+    },
+}
+
+#[derive(Clone, Debug)]
+pub struct Entry {
+    pub id: ResourceId,
+    pub name: String,
+    pub flags: u16,
+    pub value: EntryValue,
+}
+
+impl Entry {
+    fn parse(buf: &[u8], pos: usize, id: ResourceId, keys: &StringPool, strings: &StringPool) -> ExtResult<Entry> {
+        let mut r = ByteReader::at(buf, pos);
+        let size = r.u16()?;
+        let flags = r.u16()?;
+
+        if flags & ENTRY_FLAG_COMPACT != 0 {
+            let data = r.u32()?;
+            return Ok(Entry {
+                id,
+                name: keys.get(size as usize).unwrap_or_default().to_string(),
+                flags: flags & 0x00ff,
+                value: EntryValue::Simple(ResValue::decode((flags >> 8) as u8, data, strings)),
+            });
+        }
+
+        let key = r.u32()? as usize;
+        let value = if flags & ENTRY_FLAG_COMPLEX != 0 {
+            let parent = r.u32()?;
+            let count = r.u32()?;
+            r.seek(pos + size as usize)?;
+            let mut values = vec![];
+            for _ in 0..count {
+                let name = ResourceId::from_u32(r.u32()?);
+                values.push((name, read_value(&mut r, strings)?));
+            }
+            EntryValue::Complex {
+                parent: if parent == 0 { None } else { Some(ResourceId::from_u32(parent)) },
+                values,
+            }
+        } else {
+            r.seek(pos + size as usize)?;
+            EntryValue::Simple(read_value(&mut r, strings)?)
+        };
+
+        Ok(Entry {
+            id,
+            name: keys.get(key).unwrap_or_default().to_string(),
+            flags,
+            value,
+        })
+    }
+
+    pub fn is_public(&self) -> bool {
+        self.flags & ENTRY_FLAG_PUBLIC != 0
+    }
+}
+
+fn read_value(r: &mut ByteReader, strings: &StringPool) -> ExtResult<ResValue> {
+    let _size = r.u16()?;
+    let _res0 = r.u8()?;
+    let data_type = r.u8()?;
+    let data = r.u32()?;
+    Ok(ResValue::decode(data_type, data, strings))
+}
+
+/// The entries of one type under a single configuration.
+#[derive(Clone, Debug)]
+pub struct TypeConfig {
+    pub config: ResConfig,
+    /// Sorted by entry index, as both dense and sparse chunks store them.
+    pub entries: Vec<Entry>,
+}
+
+impl TypeConfig {
+    pub fn entry(&self, entry_id: u16) -> Option<&Entry> {
+        self.entries.binary_search_by_key(&entry_id, |e| e.id.entry_id())
+            .ok()
+            .map(|index| &self.entries[index])
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct ResType {
+    pub id: u8,
+    pub name: String,
+    pub spec_flags: Vec<u32>,
+    pub configs: Vec<TypeConfig>,
+}
+
+#[derive(Clone, Debug)]
+pub struct Package {
+    pub id: u8,
+    pub name: String,
+    pub types: Vec<ResType>,
+}
+
+impl Package {
+    fn parse(buf: &[u8], chunk: &Chunk, strings: &StringPool) -> ExtResult<Package> {
+        let mut r = ByteReader::at(buf, chunk.start + 8);
+        let id = r.u32()? as u8;
+        let name_units = (0..128)
+            .map(|_| r.u16())
+            .collect::<ExtResult<Vec<u16>>>()?;
+        let name_len = name_units.iter().position(|c| *c == 0).unwrap_or(name_units.len());
+        let name = String::from_utf16_lossy(&name_units[..name_len]);
+        let type_strings = r.u32()? as usize;
+        let _last_public_type = r.u32()?;
+        let key_strings = r.u32()? as usize;
+
+        let type_names = StringPool::parse(buf, &Chunk::read(buf, chunk.start + type_strings)?)?;
+        let key_names = StringPool::parse(buf, &Chunk::read(buf, chunk.start + key_strings)?)?;
+
+        let mut package = Package { id, name, types: vec![] };
+        for child in chunk.children(buf) {
+            let child = child?;
+            match child.kind {
+                RES_TABLE_TYPE_SPEC_TYPE => {
+                    let mut r = ByteReader::at(buf, child.start + 8);
+                    let type_id = r.u8()?;
+                    r.skip(3)?;
+                    let count = r.u32()?;
+                    r.seek(child.body())?;
+                    let flags = (0..count)
+                        .map(|_| r.u32())
+                        .collect::<ExtResult<Vec<u32>>>()?;
+                    package.type_mut(type_id, &type_names)?.spec_flags = flags;
+                }
+                RES_TABLE_TYPE_TYPE => {
+                    let (type_id, config) = parse_type(buf, &child, id, &key_names, strings)?;
+                    package.type_mut(type_id, &type_names)?.configs.push(config);
+                }
+                _ => {}
+            }
+        }
+        package.types.sort_by_key(|t| t.id);
+
+        Ok(package)
+    }
+
+    fn type_mut(&mut self, id: u8, type_names: &StringPool) -> ExtResult<&mut ResType> {
+        if id == 0 {
+            return Err(Error::InvalidResourceTable("type id 0".into()));
+        }
+        Ok(match self.types.iter().position(|t| t.id == id) {
+            Some(index) => &mut self.types[index],
+            None => {
+                self.types.push(ResType {
+                    id,
+                    name: type_names.get(id as usize - 1).unwrap_or_default().to_string(),
+                    spec_flags: vec![],
+                    configs: vec![],
+                });
+                self.types.last_mut().unwrap()
+            }
+        })
+    }
+
+    pub fn res_type(&self, id: u8) -> Option<&ResType> {
+        self.types.iter().find(|t| t.id == id)
+    }
+}
+
+fn parse_type(buf: &[u8], chunk: &Chunk, package_id: u8, keys: &StringPool, strings: &StringPool) -> ExtResult<(u8, TypeConfig)> {
+    let mut r = ByteReader::at(buf, chunk.start + 8);
+    let type_id = r.u8()?;
+    let flags = r.u8()?;
+    let _reserved = r.u16()?;
+    let entry_count = r.u32()? as usize;
+    let entries_start = chunk.start + r.u32()? as usize;
+    let config = ResConfig::parse(buf, r.pos())?;
+
+    if type_id == 0 {
+        return Err(Error::InvalidResourceTable("type id 0".into()));
+    }
+
+    r.seek(chunk.body())?;
+    let mut offsets: Vec<(u16, usize)> = vec![];
+    if flags & TYPE_FLAG_SPARSE != 0 {
+        for _ in 0..entry_count {
+            let index = r.u16()?;
+            offsets.push((index, r.u16()? as usize * 4));
+        }
+    } else if flags & TYPE_FLAG_OFFSET16 != 0 {
+        for index in 0..entry_count {
+            let offset = r.u16()?;
+            if offset != NO_ENTRY16 {
+                offsets.push((index as u16, offset as usize * 4));
+            }
+        }
+    } else {
+        for index in 0..entry_count {
+            let offset = r.u32()?;
+            if offset != NO_ENTRY {
+                offsets.push((index as u16, offset as usize));
+            }
+        }
+    }
+
+    let data = &buf[..chunk.end()];
+    let entries = offsets
+        .into_iter()
+        .map(|(index, offset)| {
+            let id = ResourceId::from_parts(package_id, type_id, index);
+            Entry::parse(data, entries_start + offset, id, keys, strings)
+        })
+        .collect::<ExtResult<Vec<Entry>>>()?;
+
+    Ok((type_id, TypeConfig { config, entries }))
+}
+
+/// The decoded contents of `resources.arsc`.
+#[derive(Clone, Debug, Default)]
+pub struct ResourceTable {
+    pub strings: StringPool,
+    pub packages: Vec<Package>,
+}
+
+impl ResourceTable {
+    pub fn parse(buf: &[u8]) -> ExtResult<ResourceTable> {
+        let header = Chunk::read(buf, 0)?;
+        if header.kind != RES_TABLE_TYPE {
+            return Err(Error::InvalidResourceTable("missing table header".into()));
+        }
+
+        let mut table = ResourceTable::default();
+        for chunk in header.children(buf) {
+            let chunk = chunk?;
+            match chunk.kind {
+                RES_STRING_POOL_TYPE => table.strings = StringPool::parse(buf, &chunk)?,
+                RES_TABLE_PACKAGE_TYPE => table.packages.push(Package::parse(buf, &chunk, &table.strings)?),
+                _ => {}
+            }
+        }
+
+        Ok(table)
+    }
+
+    pub fn package(&self, id: u8) -> Option<&Package> {
+        self.packages.iter().find(|p| p.id == id)
+    }
+
+    pub fn res_type(&self, id: ResourceId) -> Option<&ResType> {
+        self.package(id.package_id())
+            .and_then(|p| p.res_type(id.type_id()))
+    }
+
+    /// Every configuration that defines `id`, with its entry.
+    pub fn entries(&self, id: ResourceId) -> Vec<(&ResConfig, &Entry)> {
+        match self.res_type(id) {
+            Some(t) => t.configs
+                .iter()
+                .filter_map(|c| c.entry(id.entry_id()).map(|e| (&c.config, e)))
+                .collect(),
+            None => vec![],
+        }
+    }
+
+    /// `type/name` of a resource, as written in `@type/name` references.
+    pub fn name_of(&self, id: ResourceId) -> Option<String> {
+        let t = self.res_type(id)?;
+        let entry = t.configs.iter().find_map(|c| c.entry(id.entry_id()))?;
+        Some(format!("{}/{}", t.name, entry.name))
+    }
+
+    fn format_value(&self, value: &ResValue) -> String {
+        match value {
+            ResValue::Reference(id) | ResValue::DynamicReference(id) => match self.name_of(*id) {
+                Some(name) => format!("@{}", name),
+                None => value.to_string(),
+            },
+            ResValue::Attribute(id) | ResValue::DynamicAttribute(id) => match self.name_of(*id) {
+                Some(name) => format!("?{}", name),
+                None => value.to_string(),
+            },
+            ResValue::String(s) => format!("{:?}", s),
+            _ => value.to_string(),
+        }
+    }
+}
+
+/// Prints the table in the layout of `aapt2 dump resources`.
+impl fmt::Display for ResourceTable {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for package in &self.packages {
+            writeln!(f, "Package name={} id={:02x}", package.name, package.id)?;
+            for t in &package.types {
+                writeln!(f, "  type {} id={:02x} entryCount={}", t.name, t.id, t.spec_flags.len())?;
+                let mut ids: Vec<ResourceId> = t.configs
+                    .iter()
+                    .flat_map(|c| c.entries.iter().map(|e| e.id))
+                    .collect();
+                ids.sort();
+                ids.dedup();
+
+                for id in ids {
+                    let entries = self.entries(id);
+                    let name = entries.first().map(|(_, e)| e.name.as_str()).unwrap_or_default();
+                    let spec = t.spec_flags.get(id.entry_id() as usize).copied().unwrap_or_default();
+                    write!(f, "    resource {} {}/{}", id, t.name, name)?;
+                    if spec & 0x4000_0000 != 0 {
+                        write!(f, " PUBLIC")?;
+                    }
+                    writeln!(f)?;
+
+                    for (config, entry) in entries {
+                        match &entry.value {
+                            EntryValue::Simple(value) => {
+                                writeln!(f, "      ({}) {}", config, self.format_value(value))?;
+                            }
+                            EntryValue::Complex { parent, values } => {
+                                write!(f, "      ({})", config)?;
+                                if let Some(parent) = parent {
+                                    write!(f, " parent={}", self.format_value(&ResValue::Reference(*parent)))?;
+                                }
+                                writeln!(f, " size={}", values.len())?;
+                                for (key, value) in values {
+                                    let key = self.name_of(*key).unwrap_or_else(|| key.to_string());
+                                    writeln!(f, "        {}={}", key, self.format_value(value))?;
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,171 @@
+use core::fmt;
+use super::{ResourceId, StringPool};
+
+const TYPE_NULL: u8 = 0x00;
+const TYPE_REFERENCE: u8 = 0x01;
+const TYPE_ATTRIBUTE: u8 = 0x02;
+const TYPE_STRING: u8 = 0x03;
+const TYPE_FLOAT: u8 = 0x04;
+const TYPE_DIMENSION: u8 = 0x05;
+const TYPE_FRACTION: u8 = 0x06;
+const TYPE_DYNAMIC_REFERENCE: u8 = 0x07;
+const TYPE_DYNAMIC_ATTRIBUTE: u8 = 0x08;
+const TYPE_INT_DEC: u8 = 0x10;
+const TYPE_INT_HEX: u8 = 0x11;
+const TYPE_INT_BOOLEAN: u8 = 0x12;
+const TYPE_INT_COLOR_ARGB8: u8 = 0x1c;
+const TYPE_INT_COLOR_RGB8: u8 = 0x1d;
+const TYPE_INT_COLOR_ARGB4: u8 = 0x1e;
+const TYPE_INT_COLOR_RGB4: u8 = 0x1f;
+
+const DATA_NULL_EMPTY: u32 = 1;
+
+const RADIX_MULTS: [f32; 4] = [
+    1.0 / (1 << 8) as f32,
+    1.0 / (1 << 15) as f32,
+    1.0 / (1 << 23) as f32,
+    1.0 / (1u32 << 31) as f32,
+];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DimensionUnit {
+    Px,
+    Dp,
+    Sp,
+    Pt,
+    In,
+    Mm,
+    Unknown(u8),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FractionUnit {
+    Fraction,
+    FractionParent,
+    Unknown(u8),
+}
+
+/// A decoded `Res_value`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ResValue {
+    Null,
+    Empty,
+    Reference(ResourceId),
+    Attribute(ResourceId),
+    String(String),
+    Float(f32),
+    Dimension(f32, DimensionUnit),
+    Fraction(f32, FractionUnit),
+    DynamicReference(ResourceId),
+    DynamicAttribute(ResourceId),
+    IntDec(i32),
+    IntHex(u32),
+    Boolean(bool),
+    ColorArgb8(u32),
+    ColorRgb8(u32),
+    ColorArgb4(u32),
+    ColorRgb4(u32),
+    Unknown { data_type: u8, data: u32 },
+}
+
+impl ResValue {
+    pub fn decode(data_type: u8, data: u32, strings: &StringPool) -> ResValue {
+        match data_type {
+            TYPE_NULL if data == DATA_NULL_EMPTY => ResValue::Empty,
+            TYPE_NULL => ResValue::Null,
+            TYPE_REFERENCE => ResValue::Reference(ResourceId::from_u32(data)),
+            TYPE_ATTRIBUTE => ResValue::Attribute(ResourceId::from_u32(data)),
+            TYPE_STRING => ResValue::String(
+                strings.get(data as usize).unwrap_or_default().to_string()
+            ),
+            TYPE_FLOAT => ResValue::Float(f32::from_bits(data)),
+            TYPE_DIMENSION => {
+                let unit = match data & 0x0f {
+                    0 => DimensionUnit::Px,
+                    1 => DimensionUnit::Dp,
+                    2 => DimensionUnit::Sp,
+                    3 => DimensionUnit::Pt,
+                    4 => DimensionUnit::In,
+                    5 => DimensionUnit::Mm,
+                    u => DimensionUnit::Unknown(u as u8),
+                };
+                ResValue::Dimension(complex_to_float(data), unit)
+            }
+            TYPE_FRACTION => {
+                let unit = match data & 0x0f {
+                    0 => FractionUnit::Fraction,
+                    1 => FractionUnit::FractionParent,
+                    u => FractionUnit::Unknown(u as u8),
+                };
+                ResValue::Fraction(complex_to_float(data) * 100.0, unit)
+            }
+            TYPE_DYNAMIC_REFERENCE => ResValue::DynamicReference(ResourceId::from_u32(data)),
+            TYPE_DYNAMIC_ATTRIBUTE => ResValue::DynamicAttribute(ResourceId::from_u32(data)),
+            TYPE_INT_DEC => ResValue::IntDec(data as i32),
+            TYPE_INT_HEX => ResValue::IntHex(data),
+            TYPE_INT_BOOLEAN => ResValue::Boolean(data != 0),
+            TYPE_INT_COLOR_ARGB8 => ResValue::ColorArgb8(data),
+            TYPE_INT_COLOR_RGB8 => ResValue::ColorRgb8(data),
+            TYPE_INT_COLOR_ARGB4 => ResValue::ColorArgb4(data),
+            TYPE_INT_COLOR_RGB4 => ResValue::ColorRgb4(data),
+            _ => ResValue::Unknown { data_type, data },
+        }
+    }
+
+    /// The resource this value points at, for references of any kind.
+    pub fn reference(&self) -> Option<ResourceId> {
+        match self {
+            ResValue::Reference(id) | ResValue::DynamicReference(id) => Some(*id),
+            _ => None,
+        }
+    }
+}
+
+fn complex_to_float(data: u32) -> f32 {
+    (data & 0xffff_ff00) as i32 as f32 * RADIX_MULTS[((data >> 4) & 0x03) as usize]
+}
+
+impl fmt::Display for DimensionUnit {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DimensionUnit::Px => write!(f, "px"),
+            DimensionUnit::Dp => write!(f, "dp"),
+            DimensionUnit::Sp => write!(f, "sp"),
+            DimensionUnit::Pt => write!(f, "pt"),
+            DimensionUnit::In => write!(f, "in"),
+            DimensionUnit::Mm => write!(f, "mm"),
+            DimensionUnit::Unknown(u) => write!(f, " (unit {})", u),
+        }
+    }
+}
+
+impl fmt::Display for FractionUnit {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FractionUnit::Fraction => write!(f, "%"),
+            FractionUnit::FractionParent => write!(f, "%p"),
+            FractionUnit::Unknown(u) => write!(f, " (unit {})", u),
+        }
+    }
+}
+
+impl fmt::Display for ResValue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ResValue::Null => write!(f, "@null"),
+            ResValue::Empty => write!(f, "@empty"),
+            ResValue::Reference(id) | ResValue::DynamicReference(id) => write!(f, "@{}", id),
+            ResValue::Attribute(id) | ResValue::DynamicAttribute(id) => write!(f, "?{}", id),
+            ResValue::String(s) => write!(f, "{}", s),
+            ResValue::Float(v) => write!(f, "{}", v),
+            ResValue::Dimension(v, unit) => write!(f, "{}{}", v, unit),
+            ResValue::Fraction(v, unit) => write!(f, "{}{}", v, unit),
+            ResValue::IntDec(v) => write!(f, "{}", v),
+            ResValue::IntHex(v) => write!(f, "0x{:08x}", v),
+            ResValue::Boolean(v) => write!(f, "{}", v),
+            ResValue::ColorArgb8(v) | ResValue::ColorArgb4(v) => write!(f, "#{:08x}", v),
+            ResValue::ColorRgb8(v) | ResValue::ColorRgb4(v) => write!(f, "#{:06x}", v & 0x00ff_ffff),
+            ResValue::Unknown { data_type, data } => write!(f, "(type 0x{:02x}) 0x{:08x}", data_type, data),
+        }
+    }
+}
@@ -0,0 +1,323 @@
+use core::fmt;
+use std::collections::{BTreeMap, BTreeSet};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum SdkCategory {
+    Analytics,
+    Attribution,
+    Ads,
+    Crash,
+    Payments,
+    Push,
+    Social,
+}
+
+impl SdkCategory {
+    pub fn name(&self) -> &'static str {
+        match self {
+            SdkCategory::Analytics => "analytics",
+            SdkCategory::Attribution => "attribution",
+            SdkCategory::Ads => "ads",
+            SdkCategory::Crash => "crash",
+            SdkCategory::Payments => "payments",
+            SdkCategory::Push => "push",
+            SdkCategory::Social => "social",
+        }
+    }
+}
+
+impl fmt::Display for SdkCategory {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// What identifies an SDK inside a package. Any one match detects it.
+#[derive(Clone, Copy, Debug)]
+pub struct SdkSignature {
+    pub name: &'static str,
+    pub category: SdkCategory,
+    /// Java packages, matched with their subpackages.
+    pub dex_packages: &'static [&'static str],
+    /// Prefixes of `lib/<abi>/*.so` file names.
+    pub native_libraries: &'static [&'static str],
+    /// `Frameworks/<name>.framework` names.
+    pub frameworks: &'static [&'static str],
+    pub plist_keys: &'static [&'static str],
+    /// `<group>_<artifact>` of the `META-INF/*.version` files holding the version.
+    pub maven_artifacts: &'static [&'static str],
+}
+
+const NONE: &[&str] = &[];
+
+const fn sdk(name: &'static str, category: SdkCategory) -> SdkSignature {
+    SdkSignature { name, category, dex_packages: NONE, native_libraries: NONE, frameworks: NONE, plist_keys: NONE, maven_artifacts: NONE }
+}
+
+/// The built-in signature database.
+pub const SDK_SIGNATURES: &[SdkSignature] = &[
+    SdkSignature {
+        dex_packages: &["com.google.firebase.analytics", "com.google.android.gms.measurement"],
+        frameworks: &["FirebaseAnalytics"],
+        plist_keys: &["FIREBASE_ANALYTICS_COLLECTION_ENABLED"],
+        maven_artifacts: &["com.google.firebase_firebase-analytics", "com.google.android.gms_play-services-measurement"],
+        ..sdk("Firebase Analytics", SdkCategory::Analytics)
+    },
+    SdkSignature {
+        dex_packages: &["com.google.android.gms.analytics"],
+        frameworks: &["GoogleAnalytics"],
+        maven_artifacts: &["com.google.android.gms_play-services-analytics"],
+        ..sdk("Google Analytics", SdkCategory::Analytics)
+    },
+    SdkSignature {
+        dex_packages: &["com.umeng"],
+        native_libraries: &["libumeng"],
+        frameworks: &["UMCommon", "UMAnalytics", "UMDevice"],
+        ..sdk("Umeng", SdkCategory::Analytics)
+    },
+    SdkSignature {
+        dex_packages: &["com.mixpanel"],
+        frameworks: &["Mixpanel"],
+        ..sdk("Mixpanel", SdkCategory::Analytics)
+    },
+    SdkSignature {
+        dex_packages: &["com.amplitude"],
+        frameworks: &["Amplitude", "AmplitudeSwift"],
+        ..sdk("Amplitude", SdkCategory::Analytics)
+    },
+    SdkSignature {
+        dex_packages: &["com.segment.analytics"],
+        frameworks: &["Segment"],
+        ..sdk("Segment", SdkCategory::Analytics)
+    },
+    SdkSignature {
+        dex_packages: &["com.flurry"],
+        frameworks: &["Flurry_iOS_SDK"],
+        ..sdk("Flurry", SdkCategory::Analytics)
+    },
+    SdkSignature {
+        dex_packages: &["com.appsflyer"],
+        frameworks: &["AppsFlyerLib"],
+        ..sdk("AppsFlyer", SdkCategory::Attribution)
+    },
+    SdkSignature {
+        dex_packages: &["com.adjust.sdk"],
+        frameworks: &["AdjustSdk", "Adjust"],
+        ..sdk("Adjust", SdkCategory::Attribution)
+    },
+    SdkSignature {
+        dex_packages: &["io.branch"],
+        frameworks: &["Branch", "BranchSDK"],
+        plist_keys: &["branch_key"],
+        ..sdk("Branch", SdkCategory::Attribution)
+    },
+    SdkSignature {
+        dex_packages: &["com.google.android.gms.ads"],
+        frameworks: &["GoogleMobileAds"],
+        plist_keys: &["GADApplicationIdentifier"],
+        maven_artifacts: &["com.google.android.gms_play-services-ads", "com.google.android.gms_play-services-ads-lite"],
+        ..sdk("Google Mobile Ads", SdkCategory::Ads)
+    },
+    SdkSignature {
+        dex_packages: &["com.facebook.ads"],
+        frameworks: &["FBAudienceNetwork"],
+        ..sdk("Meta Audience Network", SdkCategory::Ads)
+    },
+    SdkSignature {
+        dex_packages: &["com.applovin"],
+        frameworks: &["AppLovinSDK"],
+        plist_keys: &["AppLovinSdkKey"],
+        ..sdk("AppLovin", SdkCategory::Ads)
+    },
+    SdkSignature {
+        dex_packages: &["com.unity3d.ads"],
+        frameworks: &["UnityAds"],
+        ..sdk("Unity Ads", SdkCategory::Ads)
+    },
+    SdkSignature {
+        dex_packages: &["com.ironsource"],
+        frameworks: &["IronSource"],
+        ..sdk("ironSource", SdkCategory::Ads)
+    },
+    SdkSignature {
+        dex_packages: &["com.bytedance.sdk.openadsdk"],
+        frameworks: &["BUAdSDK", "PAGAdSDK"],
+        ..sdk("Pangle", SdkCategory::Ads)
+    },
+    SdkSignature {
+        dex_packages: &["com.qq.e"],
+        frameworks: &["GDTMobSDK"],
+        ..sdk("Tencent Ads", SdkCategory::Ads)
+    },
+    SdkSignature {
+        dex_packages: &["com.google.firebase.crashlytics"],
+        native_libraries: &["libcrashlytics"],
+        frameworks: &["FirebaseCrashlytics"],
+        maven_artifacts: &["com.google.firebase_firebase-crashlytics"],
+        ..sdk("Firebase Crashlytics", SdkCategory::Crash)
+    },
+    SdkSignature {
+        dex_packages: &["com.tencent.bugly"],
+        native_libraries: &["libBugly"],
+        frameworks: &["Bugly"],
+        ..sdk("Bugly", SdkCategory::Crash)
+    },
+    SdkSignature {
+        dex_packages: &["io.sentry"],
+        native_libraries: &["libsentry"],
+        frameworks: &["Sentry"],
+        ..sdk("Sentry", SdkCategory::Crash)
+    },
+    SdkSignature {
+        dex_packages: &["com.bugsnag"],
+        native_libraries: &["libbugsnag"],
+        frameworks: &["Bugsnag"],
+        ..sdk("Bugsnag", SdkCategory::Crash)
+    },
+    SdkSignature {
+        dex_packages: &["com.stripe.android"],
+        frameworks: &["Stripe", "StripeCore", "StripePaymentSheet"],
+        ..sdk("Stripe", SdkCategory::Payments)
+    },
+    SdkSignature {
+        dex_packages: &["com.braintreepayments", "com.paypal"],
+        frameworks: &["Braintree", "BraintreeCore", "PayPalCheckout"],
+        ..sdk("PayPal / Braintree", SdkCategory::Payments)
+    },
+    SdkSignature {
+        dex_packages: &["com.alipay.sdk"],
+        frameworks: &["AlipaySDK"],
+        ..sdk("Alipay", SdkCategory::Payments)
+    },
+    SdkSignature {
+        dex_packages: &["com.android.billingclient"],
+        maven_artifacts: &["com.android.billingclient_billing"],
+        ..sdk("Google Play Billing", SdkCategory::Payments)
+    },
+    SdkSignature {
+        dex_packages: &["com.google.firebase.messaging"],
+        frameworks: &["FirebaseMessaging"],
+        maven_artifacts: &["com.google.firebase_firebase-messaging"],
+        ..sdk("Firebase Cloud Messaging", SdkCategory::Push)
+    },
+    SdkSignature {
+        dex_packages: &["com.onesignal"],
+        frameworks: &["OneSignal", "OneSignalFramework"],
+        ..sdk("OneSignal", SdkCategory::Push)
+    },
+    SdkSignature {
+        dex_packages: &["cn.jpush", "cn.jiguang"],
+        native_libraries: &["libjcore"],
+        frameworks: &["JPush", "JCore"],
+        ..sdk("JPush", SdkCategory::Push)
+    },
+    SdkSignature {
+        dex_packages: &["com.facebook.appevents", "com.facebook.login", "com.facebook.share"],
+        frameworks: &["FBSDKCoreKit", "FBSDKLoginKit", "FBSDKShareKit"],
+        plist_keys: &["FacebookAppID", "FacebookClientToken"],
+        ..sdk("Facebook SDK", SdkCategory::Social)
+    },
+    SdkSignature {
+        dex_packages: &["com.tencent.mm.opensdk"],
+        frameworks: &["WechatOpenSDK"],
+        ..sdk("WeChat OpenSDK", SdkCategory::Social)
+    },
+];
+
+/// What a package contains, gathered for SDK detection.
+#[derive(Clone, Debug, Default)]
+pub struct PackageContents {
+    /// Packages of the classes the DEX files reference.
+    pub dex_packages: BTreeSet<String>,
+    /// File names of the native libraries.
+    pub native_libraries: BTreeSet<String>,
+    /// Embedded framework names with their `CFBundleShortVersionString`.
+    pub frameworks: BTreeMap<String, Option<String>>,
+    /// Top-level keys of the main Info.plist.
+    pub plist_keys: BTreeSet<String>,
+    /// Versions from `META-INF/<group>_<artifact>.version` files.
+    pub maven_versions: BTreeMap<String, String>,
+}
+
+/// Third-party SDKs found in a package.
+#[derive(Clone, Debug, Default)]
+pub struct SdkReport {
+    pub sdks: Vec<DetectedSdk>,
+}
+
+#[derive(Clone, Debug)]
+pub struct DetectedSdk {
+    pub name: &'static str,
+    pub category: SdkCategory,
+    pub version: Option<String>,
+    /// What matched, e.g. `DEX package com.appsflyer`.
+    pub evidence: Vec<String>,
+}
+
+impl PackageContents {
+    /// Matches the contents against `signatures`.
+    pub fn detect(&self, signatures: &[SdkSignature]) -> SdkReport {
+        let mut sdks = vec![];
+        for signature in signatures {
+            let mut evidence = vec![];
+            let mut version = None;
+
+            for prefix in signature.dex_packages {
+                let nested = format!("{}.", prefix);
+                if self.dex_packages.iter().any(|p| p == prefix || p.starts_with(&nested)) {
+                    evidence.push(format!("DEX package {}", prefix));
+                }
+            }
+            for prefix in signature.native_libraries {
+                evidence.extend(self.native_libraries.iter()
+                    .filter(|name| name.starts_with(prefix))
+                    .map(|name| format!("native library {}", name)));
+            }
+            for name in signature.frameworks {
+                if let Some(framework_version) = self.frameworks.get(*name) {
+                    evidence.push(format!("framework {}", name));
+                    version = version.or_else(|| framework_version.clone());
+                }
+            }
+            for key in signature.plist_keys {
+                if self.plist_keys.contains(*key) {
+                    evidence.push(format!("Info.plist key {}", key));
+                }
+            }
+            for artifact in signature.maven_artifacts {
+                if let Some(artifact_version) = self.maven_versions.get(*artifact) {
+                    evidence.push(format!("META-INF/{}.version", artifact));
+                    version = version.or_else(|| Some(artifact_version.clone()));
+                }
+            }
+
+            if !evidence.is_empty() {
+                sdks.push(DetectedSdk { name: signature.name, category: signature.category, version, evidence });
+            }
+        }
+        sdks.sort_by(|a, b| a.category.cmp(&b.category).then_with(|| a.name.cmp(b.name)));
+        SdkReport { sdks }
+    }
+}
+
+impl fmt::Display for SdkReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.sdks.is_empty() {
+            return writeln!(f, "No known SDKs detected");
+        }
+        for sdk in &self.sdks {
+            writeln!(f, "{}", sdk)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for DetectedSdk {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} ({})", self.name, self.category)?;
+        if let Some(version) = &self.version {
+            write!(f, " {}", version)?;
+        }
+        write!(f, ": {}", self.evidence.join(", "))
+    }
+}
@@ -0,0 +1,178 @@
+use std::io::{Read, Seek, SeekFrom};
+use crate::{
+    bytes::ByteReader,
+    error::{Error, ExtResult},
+};
+use super::channel::{WALLE_CHANNEL_BLOCK_ID, VASDOLLY_CHANNEL_BLOCK_ID};
+
+pub const APK_SIGNATURE_SCHEME_V2_BLOCK_ID: u32 = 0x7109_871a;
+pub const APK_SIGNATURE_SCHEME_V3_BLOCK_ID: u32 = 0xf053_68c0;
+pub const APK_SIGNATURE_SCHEME_V31_BLOCK_ID: u32 = 0x1b93_ad61;
+pub const VERITY_PADDING_BLOCK_ID: u32 = 0x4272_6577;
+pub const DEPENDENCY_INFO_BLOCK_ID: u32 = 0x504b_4453;
+pub const SOURCE_STAMP_V1_BLOCK_ID: u32 = 0x2b09_189e;
+pub const SOURCE_STAMP_V2_BLOCK_ID: u32 = 0x6dff_800d;
+
+const APK_SIG_BLOCK_MAGIC: &[u8; 16] = b"APK Sig Block 42";
+const EOCD_SIGNATURE: u32 = 0x0605_4b50;
+const EOCD_MIN_SIZE: usize = 22;
+
+/// Where the central directory and end of central directory record live.
+#[derive(Clone, Debug)]
+pub(crate) struct ZipSections {
+    pub cd_offset: u64,
+    pub cd_size: u64,
+    pub eocd: Vec<u8>,
+}
+
+pub(crate) fn find_zip_sections<R: Read + Seek>(reader: &mut R) -> ExtResult<ZipSections> {
+    let file_size = reader.seek(SeekFrom::End(0))?;
+    let tail_size = file_size.min((EOCD_MIN_SIZE + u16::MAX as usize) as u64);
+    reader.seek(SeekFrom::Start(file_size - tail_size))?;
+    let mut tail = vec![0u8; tail_size as usize];
+    reader.read_exact(&mut tail)?;
+
+    // The EOCD is the last record whose comment length reaches exactly to the end of file.
+    let start = (0..=tail.len().saturating_sub(EOCD_MIN_SIZE))
+        .rev()
+        .find(|pos| {
+            let mut r = ByteReader::at(&tail, *pos);
+            let comment = ByteReader::at(&tail, pos + 20).u16().unwrap_or(u16::MAX) as usize;
+            r.u32().ok() == Some(EOCD_SIGNATURE) && pos + EOCD_MIN_SIZE + comment == tail.len()
+        })
+        .ok_or(Error::InvalidFile)?;
+
+    let mut r = ByteReader::at(&tail, start + 12);
+    let cd_size = r.u32()? as u64;
+    let cd_offset = r.u32()? as u64;
+    let eocd_offset = file_size - tail_size + start as u64;
+    if cd_offset + cd_size > eocd_offset {
+        return Err(Error::InvalidFile);
+    }
+
+    Ok(ZipSections {
+        cd_offset,
+        cd_size,
+        eocd: tail[start..].to_vec(),
+    })
+}
+
+/// The APK Signing Block: ID-value pairs stored right before the central directory.
+#[derive(Clone, Debug)]
+pub(crate) struct SigningBlock {
+    /// File offset of the block, where the signed ZIP entries end.
+    pub offset: u64,
+    pub pairs: Vec<(u32, Vec<u8>)>,
+}
+
+impl SigningBlock {
+    pub fn get(&self, id: u32) -> Option<&[u8]> {
+        self.pairs.iter()
+            .find(|(i, _)| *i == id)
+            .map(|(_, v)| v.as_slice())
+    }
+}
+
+pub(crate) fn find_signing_block<R: Read + Seek>(reader: &mut R, sections: &ZipSections) -> ExtResult<Option<SigningBlock>> {
+    if sections.cd_offset < 32 {
+        return Ok(None);
+    }
+    reader.seek(SeekFrom::Start(sections.cd_offset - 24))?;
+    let mut footer = [0u8; 24];
+    reader.read_exact(&mut footer)?;
+    if &footer[8..] != APK_SIG_BLOCK_MAGIC {
+        return Ok(None);
+    }
+
+    let size = ByteReader::at(&footer, 0).u64()?;
+    let offset = (sections.cd_offset)
+        .checked_sub(size)
+        .and_then(|o| o.checked_sub(8))
+        .ok_or_else(|| invalid("block size out of range"))?;
+    if size < 24 || size > i32::MAX as u64 {
+        return Err(invalid("block size out of range"));
+    }
+
+    reader.seek(SeekFrom::Start(offset))?;
+    let mut block = vec![0u8; size as usize + 8];
+    reader.read_exact(&mut block)?;
+    if ByteReader::at(&block, 0).u64()? != size {
+        return Err(invalid("block sizes in header and footer differ"));
+    }
+
+    let mut pairs = vec![];
+    let mut r = ByteReader::at(&block[..block.len() - 24], 8);
+    while r.remaining() > 0 {
+        let len = r.u64()?;
+        if len < 4 || len > r.remaining() as u64 {
+            return Err(invalid("pair length out of range"));
+        }
+        let id = r.u32()?;
+        pairs.push((id, r.bytes(len as usize - 4)?.to_vec()));
+    }
+
+    Ok(Some(SigningBlock { offset, pairs }))
+}
+
+/// Encodes ID-value pairs as an APK Signing Block. With `align`, a verity
+/// padding pair rounds the block up to a multiple of 4096 bytes.
+pub(crate) fn encode_signing_block(pairs: &[(u32, Vec<u8>)], align: bool) -> Vec<u8> {
+    let mut body = vec![];
+    let push = |body: &mut Vec<u8>, id: u32, value: &[u8]| {
+        body.extend_from_slice(&(value.len() as u64 + 4).to_le_bytes());
+        body.extend_from_slice(&id.to_le_bytes());
+        body.extend_from_slice(value);
+    };
+    for (id, value) in pairs {
+        push(&mut body, *id, value);
+    }
+    if align {
+        let len = 8 + body.len() + 12 + 24;
+        let padding = (4096 - len % 4096) % 4096;
+        push(&mut body, VERITY_PADDING_BLOCK_ID, &vec![0; padding]);
+    }
+
+    let size = (body.len() + 24) as u64;
+    let mut block = Vec::with_capacity(body.len() + 32);
+    block.extend_from_slice(&size.to_le_bytes());
+    block.extend_from_slice(&body);
+    block.extend_from_slice(&size.to_le_bytes());
+    block.extend_from_slice(APK_SIG_BLOCK_MAGIC);
+    block
+}
+
+/// Reads a `uint32` length-prefixed value.
+pub(crate) fn prefixed<'a>(r: &mut ByteReader<'a>) -> ExtResult<&'a [u8]> {
+    let len = r.u32()? as usize;
+    r.bytes(len)
+}
+
+/// Splits a length-prefixed sequence of length-prefixed items.
+pub(crate) fn sequence(buf: &[u8]) -> ExtResult<Vec<&[u8]>> {
+    let mut r = ByteReader::at(buf, 0);
+    let mut items = vec![];
+    while r.remaining() > 0 {
+        items.push(prefixed(&mut r)?);
+    }
+    Ok(items)
+}
+
+pub(crate) fn invalid(msg: &str) -> Error {
+    Error::InvalidSigningBlock(msg.to_string())
+}
+
+/// Human readable name of a known APK Signing Block pair ID.
+pub fn block_name(id: u32) -> &'static str {
+    match id {
+        APK_SIGNATURE_SCHEME_V2_BLOCK_ID => "APK Signature Scheme v2",
+        APK_SIGNATURE_SCHEME_V3_BLOCK_ID => "APK Signature Scheme v3",
+        APK_SIGNATURE_SCHEME_V31_BLOCK_ID => "APK Signature Scheme v3.1",
+        VERITY_PADDING_BLOCK_ID => "verity padding",
+        DEPENDENCY_INFO_BLOCK_ID => "dependency info",
+        SOURCE_STAMP_V1_BLOCK_ID => "source stamp v1",
+        SOURCE_STAMP_V2_BLOCK_ID => "source stamp v2",
+        WALLE_CHANNEL_BLOCK_ID => "Walle channel",
+        VASDOLLY_CHANNEL_BLOCK_ID => "VasDolly channel",
+        _ => "unknown",
+    }
+}
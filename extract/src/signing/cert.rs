@@ -0,0 +1,104 @@
+use core::fmt;
+use md5::Md5;
+use sha1::Sha1;
+use sha2::{Digest, Sha256};
+use x509_parser::{
+    certificate::X509Certificate,
+    objects::{oid2abbrev, oid_registry},
+    prelude::FromDer,
+    public_key::PublicKey,
+    x509::X509Name,
+};
+use crate::{
+    bytes::to_hex,
+    date::format_timestamp,
+    error::{Error, ExtResult},
+};
+
+/// An X.509 certificate as reported by `apksigner verify --print-certs`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Certificate {
+    pub subject: String,
+    pub issuer: String,
+    pub serial: String,
+    /// Unix timestamps.
+    pub not_before: i64,
+    pub not_after: i64,
+    pub key_algorithm: String,
+    pub key_size: usize,
+    /// DER encoded SubjectPublicKeyInfo.
+    pub public_key: Vec<u8>,
+    pub md5: String,
+    pub sha1: String,
+    pub sha256: String,
+    pub der: Vec<u8>,
+}
+
+impl Certificate {
+    pub fn from_der(der: &[u8]) -> ExtResult<Certificate> {
+        let (_, cert) = X509Certificate::from_der(der)
+            .map_err(|err| Error::InvalidDer(err.to_string()))?;
+        let spki = cert.public_key();
+        let (key_algorithm, key_size) = match spki.parsed() {
+            Ok(key @ PublicKey::RSA(_)) => ("RSA".to_string(), key.key_size()),
+            Ok(key @ PublicKey::EC(_)) => ("EC".to_string(), key.key_size()),
+            Ok(key @ PublicKey::DSA(_)) => ("DSA".to_string(), key.key_size()),
+            _ => (spki.algorithm.algorithm.to_id_string(), 0),
+        };
+        let serial = cert.raw_serial()
+            .iter()
+            .skip_while(|b| **b == 0)
+            .copied()
+            .collect::<Vec<u8>>();
+
+        Ok(Certificate {
+            subject: format_name(cert.subject()),
+            issuer: format_name(cert.issuer()),
+            serial: if serial.is_empty() { "0".into() } else { to_hex(&serial) },
+            not_before: cert.validity().not_before.timestamp(),
+            not_after: cert.validity().not_after.timestamp(),
+            key_algorithm,
+            key_size,
+            public_key: spki.raw.to_vec(),
+            md5: to_hex(&Md5::digest(der)),
+            sha1: to_hex(&Sha1::digest(der)),
+            sha256: to_hex(&Sha256::digest(der)),
+            der: der.to_vec(),
+        })
+    }
+}
+
+/// RFC 2253 order (most specific first), as printed by apksigner and keytool.
+fn format_name(name: &X509Name) -> String {
+    name.iter_rdn()
+        .collect::<Vec<_>>()
+        .into_iter()
+        .rev()
+        .flat_map(|rdn| rdn.iter())
+        .map(|attr| {
+            let key = oid2abbrev(attr.attr_type(), oid_registry())
+                .map(ToString::to_string)
+                .unwrap_or_else(|_| attr.attr_type().to_id_string());
+            let value = attr.as_str()
+                .map(ToString::to_string)
+                .unwrap_or_else(|_| format!("#{}", to_hex(attr.as_slice())));
+            format!("{}={}", key, value)
+        })
+        .collect::<Vec<String>>()
+        .join(", ")
+}
+
+impl fmt::Display for Certificate {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "certificate DN: {}", self.subject)?;
+        writeln!(f, "certificate issuer: {}", self.issuer)?;
+        writeln!(f, "certificate serial: {}", self.serial)?;
+        writeln!(f, "certificate valid from: {}", format_timestamp(self.not_before))?;
+        writeln!(f, "certificate valid until: {}", format_timestamp(self.not_after))?;
+        writeln!(f, "certificate SHA-256 digest: {}", self.sha256)?;
+        writeln!(f, "certificate SHA-1 digest: {}", self.sha1)?;
+        writeln!(f, "certificate MD5 digest: {}", self.md5)?;
+        writeln!(f, "key algorithm: {}", self.key_algorithm)?;
+        write!(f, "key size (bits): {}", self.key_size)
+    }
+}
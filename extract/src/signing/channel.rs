@@ -0,0 +1,149 @@
+use core::fmt;
+use std::{
+    collections::BTreeMap,
+    io::{self, Read, Seek, SeekFrom, Write},
+};
+use crate::{
+    bytes::ByteReader,
+    error::{Error, ExtResult},
+};
+use super::block::{
+    find_zip_sections, find_signing_block, encode_signing_block, invalid, ZipSections,
+    VERITY_PADDING_BLOCK_ID,
+};
+
+/// Walle stores a JSON object with a `channel` key and any extra key/values.
+pub const WALLE_CHANNEL_BLOCK_ID: u32 = 0x7177_7777;
+/// VasDolly stores the bare channel string.
+pub const VASDOLLY_CHANNEL_BLOCK_ID: u32 = 0x8811_55ff;
+/// VasDolly v1-only APKs end the ZIP comment with `<channel><u16 length>ltlovezh`.
+const VASDOLLY_V1_MAGIC: &[u8; 8] = b"ltlovezh";
+const WALLE_CHANNEL_KEY: &str = "channel";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ChannelSource {
+    Walle,
+    VasDolly,
+    /// VasDolly's ZIP comment, used when the APK has no v2 signature.
+    ZipComment,
+}
+
+/// A distribution channel injected after signing.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ChannelInfo {
+    pub source: ChannelSource,
+    pub channel: Option<String>,
+    /// Extra Walle key/value payloads besides the channel.
+    pub extras: BTreeMap<String, String>,
+}
+
+impl ChannelInfo {
+    /// Parses a Walle block value.
+    pub fn from_walle(value: &[u8]) -> ExtResult<ChannelInfo> {
+        let json: serde_json::Map<String, serde_json::Value> = serde_json::from_slice(value)
+            .map_err(|err| Error::InvalidChannel(err.to_string()))?;
+        let mut extras = json.into_iter()
+            .map(|(k, v)| match v {
+                serde_json::Value::String(s) => (k, s),
+                v => (k, v.to_string()),
+            })
+            .collect::<BTreeMap<String, String>>();
+
+        Ok(ChannelInfo {
+            source: ChannelSource::Walle,
+            channel: extras.remove(WALLE_CHANNEL_KEY),
+            extras,
+        })
+    }
+
+    fn from_vasdolly(value: &[u8], source: ChannelSource) -> ExtResult<ChannelInfo> {
+        let channel = String::from_utf8(value.to_vec())
+            .map_err(|err| Error::InvalidChannel(err.to_string()))?;
+        Ok(ChannelInfo {
+            source,
+            channel: Some(channel),
+            extras: BTreeMap::new(),
+        })
+    }
+}
+
+/// Reads a Walle or VasDolly channel from the APK Signing Block, falling back
+/// to the VasDolly ZIP comment.
+pub fn read_channel<R: Read + Seek>(reader: &mut R) -> ExtResult<Option<ChannelInfo>> {
+    let sections = find_zip_sections(reader)?;
+    if let Some(block) = find_signing_block(reader, &sections)? {
+        if let Some(value) = block.get(WALLE_CHANNEL_BLOCK_ID) {
+            return ChannelInfo::from_walle(value).map(Some);
+        }
+        if let Some(value) = block.get(VASDOLLY_CHANNEL_BLOCK_ID) {
+            return ChannelInfo::from_vasdolly(value, ChannelSource::VasDolly).map(Some);
+        }
+    }
+
+    match zip_comment_channel(&sections) {
+        Some(value) => ChannelInfo::from_vasdolly(value, ChannelSource::ZipComment).map(Some),
+        None => Ok(None),
+    }
+}
+
+/// Copies the APK from `reader` to `writer` with a Walle channel pair inserted
+/// into, or replaced in, its signing block. v2 and v3 signatures stay valid as
+/// they don't cover the signing block, and the EOCD central directory offset
+/// they digest is the block's offset either way. A v4 signature (`<apk>.idsig`)
+/// hashes the whole file, so the one of the input does not match the output.
+pub fn write_channel<R: Read + Seek, W: Write>(
+    reader: &mut R,
+    writer: &mut W,
+    channel: &str,
+    extras: &BTreeMap<String, String>,
+) -> ExtResult<()> {
+    let sections = find_zip_sections(reader)?;
+    let block = find_signing_block(reader, &sections)?
+        .ok_or_else(|| invalid("no signing block, channels need a v2 or later signature"))?;
+
+    let mut json = extras.iter()
+        .map(|(k, v)| (k.clone(), serde_json::Value::String(v.clone())))
+        .collect::<serde_json::Map<String, serde_json::Value>>();
+    json.insert(WALLE_CHANNEL_KEY.to_string(), serde_json::Value::String(channel.to_string()));
+    let value = serde_json::to_vec(&json).map_err(|err| Error::InvalidChannel(err.to_string()))?;
+
+    let align = block.get(VERITY_PADDING_BLOCK_ID).is_some();
+    let mut pairs = block.pairs.into_iter()
+        .filter(|(id, _)| *id != WALLE_CHANNEL_BLOCK_ID && *id != VERITY_PADDING_BLOCK_ID)
+        .collect::<Vec<(u32, Vec<u8>)>>();
+    pairs.push((WALLE_CHANNEL_BLOCK_ID, value));
+    let encoded = encode_signing_block(&pairs, align);
+
+    let cd_offset = u32::try_from(block.offset + encoded.len() as u64)
+        .map_err(|_| invalid("central directory offset out of range"))?;
+    let mut eocd = sections.eocd.clone();
+    eocd[16..20].copy_from_slice(&cd_offset.to_le_bytes());
+
+    reader.seek(SeekFrom::Start(0))?;
+    io::copy(&mut reader.by_ref().take(block.offset), writer)?;
+    writer.write_all(&encoded)?;
+    reader.seek(SeekFrom::Start(sections.cd_offset))?;
+    io::copy(&mut reader.by_ref().take(sections.cd_size), writer)?;
+    writer.write_all(&eocd)?;
+    writer.flush()?;
+
+    Ok(())
+}
+
+fn zip_comment_channel(sections: &ZipSections) -> Option<&[u8]> {
+    let comment = sections.eocd.get(22..)?;
+    let rest = comment.strip_suffix(VASDOLLY_V1_MAGIC)?;
+    let len_pos = rest.len().checked_sub(2)?;
+    let len = ByteReader::at(rest, len_pos).u16().ok()? as usize;
+    rest.get(len_pos.checked_sub(len)?..len_pos)
+}
+
+impl fmt::Display for ChannelInfo {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.channel.as_deref().unwrap_or_default())?;
+        for (k, v) in &self.extras {
+            write!(f, ", {}={}", k, v)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,138 @@
+use md5::Md5;
+use sha1::Sha1;
+use sha2::{Digest, Sha256, Sha384, Sha512};
+use rsa::{
+    pkcs8::DecodePublicKey,
+    Pkcs1v15Sign, Pss, RsaPublicKey,
+};
+use p256::ecdsa::signature::hazmat::PrehashVerifier;
+use crate::der::{DerReader, TAG_SEQUENCE};
+
+const OID_RSA: &str = "1.2.840.113549.1.1.1";
+const OID_EC: &str = "1.2.840.10045.2.1";
+const OID_DSA: &str = "1.2.840.10040.4.1";
+const OID_P256: &str = "1.2.840.10045.3.1.7";
+const OID_P384: &str = "1.3.132.0.34";
+
+/// Declared weakest first, so the strongest of several compares greatest.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub(crate) enum DigestAlgorithm {
+    Md5,
+    Sha1,
+    Sha256,
+    Sha384,
+    Sha512,
+}
+
+impl DigestAlgorithm {
+    pub fn from_oid(oid: &str) -> Option<DigestAlgorithm> {
+        match oid {
+            "1.2.840.113549.2.5" => Some(DigestAlgorithm::Md5),
+            "1.3.14.3.2.26" => Some(DigestAlgorithm::Sha1),
+            "2.16.840.1.101.3.4.2.1" => Some(DigestAlgorithm::Sha256),
+            "2.16.840.1.101.3.4.2.2" => Some(DigestAlgorithm::Sha384),
+            "2.16.840.1.101.3.4.2.3" => Some(DigestAlgorithm::Sha512),
+            _ => None,
+        }
+    }
+
+    /// Algorithm named in a JAR manifest attribute such as `SHA-256-Digest`.
+    pub fn from_jar_name(name: &str) -> Option<DigestAlgorithm> {
+        match name.to_ascii_uppercase().as_str() {
+            "MD5" => Some(DigestAlgorithm::Md5),
+            "SHA1" | "SHA-1" => Some(DigestAlgorithm::Sha1),
+            "SHA-256" => Some(DigestAlgorithm::Sha256),
+            "SHA-384" => Some(DigestAlgorithm::Sha384),
+            "SHA-512" => Some(DigestAlgorithm::Sha512),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            DigestAlgorithm::Md5 => "MD5",
+            DigestAlgorithm::Sha1 => "SHA-1",
+            DigestAlgorithm::Sha256 => "SHA-256",
+            DigestAlgorithm::Sha384 => "SHA-384",
+            DigestAlgorithm::Sha512 => "SHA-512",
+        }
+    }
+
+    pub fn digest(&self, data: &[u8]) -> Vec<u8> {
+        match self {
+            DigestAlgorithm::Md5 => Md5::digest(data).to_vec(),
+            DigestAlgorithm::Sha1 => Sha1::digest(data).to_vec(),
+            DigestAlgorithm::Sha256 => Sha256::digest(data).to_vec(),
+            DigestAlgorithm::Sha384 => Sha384::digest(data).to_vec(),
+            DigestAlgorithm::Sha512 => Sha512::digest(data).to_vec(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum RsaPadding {
+    Pkcs1,
+    Pss,
+}
+
+/// Verifies `signature` over data whose `digest` was already computed, with
+/// the key in a DER encoded SubjectPublicKeyInfo. RSA, ECDSA (P-256, P-384)
+/// and DSA keys are supported.
+pub(crate) fn verify_prehashed(
+    spki: &[u8],
+    algorithm: DigestAlgorithm,
+    digest: &[u8],
+    signature: &[u8],
+    padding: RsaPadding,
+) -> Result<(), String> {
+    let (key_oid, curve) = key_algorithm(spki).ok_or("malformed public key")?;
+
+    match key_oid.as_str() {
+        OID_RSA => {
+            let key = RsaPublicKey::from_public_key_der(spki).map_err(|e| e.to_string())?;
+            let result = match (padding, algorithm) {
+                (RsaPadding::Pkcs1, DigestAlgorithm::Md5) => return Err("MD5 with RSA is not supported".into()),
+                (RsaPadding::Pkcs1, DigestAlgorithm::Sha1) => key.verify(Pkcs1v15Sign::new::<Sha1>(), digest, signature),
+                (RsaPadding::Pkcs1, DigestAlgorithm::Sha256) => key.verify(Pkcs1v15Sign::new::<Sha256>(), digest, signature),
+                (RsaPadding::Pkcs1, DigestAlgorithm::Sha384) => key.verify(Pkcs1v15Sign::new::<Sha384>(), digest, signature),
+                (RsaPadding::Pkcs1, DigestAlgorithm::Sha512) => key.verify(Pkcs1v15Sign::new::<Sha512>(), digest, signature),
+                (RsaPadding::Pss, DigestAlgorithm::Sha256) => key.verify(Pss::new::<Sha256>(), digest, signature),
+                (RsaPadding::Pss, DigestAlgorithm::Sha512) => key.verify(Pss::new::<Sha512>(), digest, signature),
+                (RsaPadding::Pss, _) => return Err("unsupported RSA-PSS digest".into()),
+            };
+            result.map_err(failed)
+        }
+        OID_EC => match curve.as_deref() {
+            Some(OID_P256) => {
+                let key = p256::ecdsa::VerifyingKey::from_public_key_der(spki).map_err(|e| e.to_string())?;
+                let signature = p256::ecdsa::Signature::from_der(signature).map_err(|e| e.to_string())?;
+                key.verify_prehash(digest, &signature).map_err(failed)
+            }
+            Some(OID_P384) => {
+                let key = p384::ecdsa::VerifyingKey::from_public_key_der(spki).map_err(|e| e.to_string())?;
+                let signature = p384::ecdsa::Signature::from_der(signature).map_err(|e| e.to_string())?;
+                key.verify_prehash(digest, &signature).map_err(failed)
+            }
+            _ => Err("unsupported elliptic curve".into()),
+        },
+        OID_DSA => {
+            let key = dsa::VerifyingKey::from_public_key_der(spki).map_err(|e| e.to_string())?;
+            let signature = dsa::Signature::try_from(signature).map_err(|e| e.to_string())?;
+            key.verify_prehash(digest, &signature).map_err(failed)
+        }
+        oid => Err(format!("unsupported key algorithm {}", oid)),
+    }
+}
+
+fn failed<E>(_: E) -> String {
+    "signature does not verify".to_string()
+}
+
+/// Key algorithm OID and, for EC keys, the named curve OID of a SubjectPublicKeyInfo.
+fn key_algorithm(spki: &[u8]) -> Option<(String, Option<String>)> {
+    let spki = DerReader::new(spki).expect(TAG_SEQUENCE).ok()?;
+    let mut algorithm = spki.children().expect(TAG_SEQUENCE).ok()?.children();
+    let oid = algorithm.next().ok()?.oid().ok()?;
+    let curve = algorithm.next().ok().and_then(|p| p.oid().ok());
+    Some((oid, curve))
+}
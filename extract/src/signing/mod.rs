@@ -0,0 +1,109 @@
+use core::fmt;
+use std::io::{Read, Seek};
+
+mod block;
+mod cert;
+mod channel;
+pub(crate) mod crypto;
+mod v1;
+mod v2;
+mod v4;
+mod verify;
+
+use block::{
+    find_zip_sections, find_signing_block,
+    APK_SIGNATURE_SCHEME_V2_BLOCK_ID, APK_SIGNATURE_SCHEME_V3_BLOCK_ID, APK_SIGNATURE_SCHEME_V31_BLOCK_ID,
+};
+use crate::error::ExtResult;
+
+/// Who signed an APK, per signature scheme.
+#[derive(Clone, Debug, Default)]
+pub struct SigningInfo {
+    pub v1: Vec<V1Signer>,
+    pub v2: Vec<ApkSigner>,
+    pub v3: Vec<ApkSigner>,
+    pub v31: Vec<ApkSigner>,
+    pub v4: Option<V4Signature>,
+    /// IDs of every pair in the APK Signing Block, signature schemes included.
+    pub block_ids: Vec<u32>,
+}
+
+/// Reads the v2, v3 and v3.1 signers from the APK Signing Block, if any.
+pub fn read_block_signers<R: Read + Seek>(reader: &mut R) -> ExtResult<SigningInfo> {
+    let mut info = SigningInfo::default();
+    let sections = find_zip_sections(reader)?;
+    let block = match find_signing_block(reader, &sections)? {
+        Some(block) => block,
+        None => return Ok(info),
+    };
+
+    if let Some(value) = block.get(APK_SIGNATURE_SCHEME_V2_BLOCK_ID) {
+        info.v2 = v2::parse_signers(value, false)?;
+    }
+    if let Some(value) = block.get(APK_SIGNATURE_SCHEME_V3_BLOCK_ID) {
+        info.v3 = v2::parse_signers(value, true)?;
+    }
+    if let Some(value) = block.get(APK_SIGNATURE_SCHEME_V31_BLOCK_ID) {
+        info.v31 = v2::parse_signers(value, true)?;
+    }
+    info.block_ids = block.pairs.iter().map(|(id, _)| *id).collect();
+
+    Ok(info)
+}
+
+fn write_certificate(f: &mut fmt::Formatter, prefix: &str, cert: &Certificate) -> fmt::Result {
+    for line in cert.to_string().lines() {
+        writeln!(f, "{} {}", prefix, line)?;
+    }
+    Ok(())
+}
+
+/// Prints signers the way `apksigner verify --print-certs` does, per scheme.
+impl fmt::Display for SigningInfo {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "Signed using v1 scheme (JAR signing): {}", !self.v1.is_empty())?;
+        writeln!(f, "Signed using v2 scheme (APK Signature Scheme v2): {}", !self.v2.is_empty())?;
+        writeln!(f, "Signed using v3 scheme (APK Signature Scheme v3): {}", !self.v3.is_empty())?;
+        writeln!(f, "Signed using v3.1 scheme (APK Signature Scheme v3.1): {}", !self.v31.is_empty())?;
+        writeln!(f, "Signed using v4 scheme (APK Signature Scheme v4): {}", self.v4.is_some())?;
+        for id in &self.block_ids {
+            writeln!(f, "Signing block entry: 0x{:08x} ({})", id, block_name(*id))?;
+        }
+
+        for (i, signer) in self.v1.iter().enumerate() {
+            if let Some(cert) = signer.certificates.first() {
+                write_certificate(f, &format!("v1 Signer #{}", i + 1), cert)?;
+            }
+        }
+        for (scheme, signers) in [("v2", &self.v2), ("v3", &self.v3), ("v3.1", &self.v31)] {
+            for (i, signer) in signers.iter().enumerate() {
+                let prefix = format!("{} Signer #{}", scheme, i + 1);
+                if let Some(cert) = signer.certificates.first() {
+                    write_certificate(f, &prefix, cert)?;
+                }
+                for (algorithm, _) in &signer.signatures {
+                    writeln!(f, "{} signature algorithm: {}", prefix, algorithm_name(*algorithm))?;
+                }
+                if let (Some(min), Some(max)) = (signer.min_sdk, signer.max_sdk) {
+                    writeln!(f, "{} SDK range: {}-{}", prefix, min, max)?;
+                }
+                for (level, node) in signer.lineage.iter().enumerate() {
+                    writeln!(f, "{} lineage #{}: {}", prefix, level + 1, node)?;
+                }
+            }
+        }
+        if let Some(v4) = &self.v4 {
+            write_certificate(f, "v4 Signer #1", &v4.certificate)?;
+        }
+
+        Ok(())
+    }
+}
+
+pub use block::block_name;
+pub use cert::Certificate;
+pub use channel::{ChannelInfo, ChannelSource, read_channel, write_channel, WALLE_CHANNEL_BLOCK_ID, VASDOLLY_CHANNEL_BLOCK_ID};
+pub use v1::{V1Signer, read_v1_signers};
+pub use v2::{ApkSigner, LineageNode, algorithm_name};
+pub use v4::V4Signature;
+pub use verify::{SchemeVerification, Verification, verify_apk};
@@ -0,0 +1,289 @@
+use std::{
+    collections::HashSet,
+    io::{Read, Seek},
+};
+use regex::Regex;
+use lazy_static::lazy_static;
+use zip::ZipArchive;
+use crate::{
+    der::TAG_SET,
+    error::ExtResult,
+    pkcs7::SignedData,
+};
+use super::{
+    cert::Certificate,
+    crypto::{verify_prehashed, DigestAlgorithm, RsaPadding},
+    verify::SchemeVerification,
+};
+
+const MANIFEST_PATH: &str = "META-INF/MANIFEST.MF";
+const OID_RSASSA_PSS: &str = "1.2.840.113549.1.1.10";
+
+lazy_static! {
+    static ref SIGNATURE_BLOCK_PATH: Regex = Regex::new(r"(?i)^META-INF/([^/]+)\.(RSA|DSA|EC)$").unwrap();
+    static ref SIGNATURE_FILE_PATH: Regex = Regex::new(r"(?i)^META-INF/[^/]+\.(SF|RSA|DSA|EC)$").unwrap();
+}
+
+/// A JAR (v1) signer: `META-INF/<name>.SF` signed by `META-INF/<name>.RSA|DSA|EC`.
+#[derive(Clone, Debug)]
+pub struct V1Signer {
+    pub name: String,
+    pub block: String,
+    /// The signing certificate first, followed by the rest of the chain.
+    pub certificates: Vec<Certificate>,
+}
+
+/// `(signer name, signature block path)` of every v1 signer, sorted by name.
+fn signature_blocks<R: Read + Seek>(archive: &ZipArchive<R>) -> Vec<(String, String)> {
+    let mut blocks: Vec<(String, String)> = archive.file_names()
+        .filter_map(|n| {
+            SIGNATURE_BLOCK_PATH.captures(n)
+                .map(|c| (c[1].to_string(), n.to_string()))
+        })
+        .collect();
+    blocks.sort();
+    blocks
+}
+
+fn read_entry<R: Read + Seek>(archive: &mut ZipArchive<R>, name: &str) -> ExtResult<Vec<u8>> {
+    let mut buf: Vec<u8> = Vec::new();
+    archive.by_name(name)?
+        .read_to_end(&mut buf)?;
+    Ok(buf)
+}
+
+pub fn read_v1_signers<R: Read + Seek>(archive: &mut ZipArchive<R>) -> ExtResult<Vec<V1Signer>> {
+    let mut signers = vec![];
+    for (name, block) in signature_blocks(archive) {
+        let buf = read_entry(archive, &block)?;
+
+        let signed_data = SignedData::parse(&buf)?;
+        let mut ders = signed_data.certificates.clone();
+        if let Some(signer) = signed_data.signers.first()
+            .and_then(|s| signed_data.signer_certificate(s))
+        {
+            ders.retain(|der| *der != signer);
+            ders.insert(0, signer);
+        }
+
+        signers.push(V1Signer {
+            name,
+            block,
+            certificates: ders
+                .into_iter()
+                .map(Certificate::from_der)
+                .collect::<ExtResult<Vec<Certificate>>>()?,
+        });
+    }
+
+    Ok(signers)
+}
+
+/// A section of a JAR manifest or signature file: its exact bytes, blank line
+/// included, and its attributes with continuation lines joined.
+struct Section<'a> {
+    raw: &'a [u8],
+    attrs: Vec<(String, String)>,
+}
+
+impl<'a> Section<'a> {
+    fn get(&self, name: &str) -> Option<&str> {
+        self.attrs.iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.as_str())
+    }
+
+    /// The strongest supported `<algorithm><suffix>` digest, e.g. `SHA-256-Digest`.
+    fn digest(&self, suffix: &str) -> Option<(DigestAlgorithm, Vec<u8>)> {
+        self.attrs.iter()
+            .filter_map(|(k, v)| {
+                let algorithm = k.len()
+                    .checked_sub(suffix.len())
+                    .filter(|i| k.is_char_boundary(*i) && k[*i..].eq_ignore_ascii_case(suffix))
+                    .and_then(|i| DigestAlgorithm::from_jar_name(&k[..i]))?;
+                Some((algorithm, base64::decode(v).ok()?))
+            })
+            .max_by_key(|(algorithm, _)| *algorithm)
+    }
+}
+
+/// Splits a manifest into its main section followed by the per-entry sections.
+fn parse_sections(buf: &[u8]) -> Vec<Section<'_>> {
+    let mut sections = vec![];
+    let mut attrs: Vec<(Vec<u8>, Vec<u8>)> = vec![];
+    let (mut start, mut pos) = (0, 0);
+
+    let mut finish = |attrs: &mut Vec<(Vec<u8>, Vec<u8>)>, raw| {
+        if !attrs.is_empty() {
+            sections.push(Section {
+                raw,
+                attrs: attrs.drain(..)
+                    .map(|(k, v)| (String::from_utf8_lossy(&k).into_owned(), String::from_utf8_lossy(&v).into_owned()))
+                    .collect(),
+            });
+        }
+    };
+
+    while pos < buf.len() {
+        let end = buf[pos..].iter()
+            .position(|b| *b == b'\r' || *b == b'\n')
+            .map_or(buf.len(), |i| pos + i);
+        let mut next = end;
+        if buf.get(next) == Some(&b'\r') {
+            next += 1;
+        }
+        if buf.get(next) == Some(&b'\n') {
+            next += 1;
+        }
+
+        let line = &buf[pos..end];
+        if line.is_empty() {
+            finish(&mut attrs, &buf[start..next]);
+            start = next;
+        } else if let Some(rest) = line.strip_prefix(b" ") {
+            // Lines are wrapped at 72 bytes, possibly inside a UTF-8 sequence.
+            if let Some((_, value)) = attrs.last_mut() {
+                value.extend_from_slice(rest);
+            }
+        } else if let Some(i) = line.iter().position(|b| *b == b':') {
+            let value = &line[i + 1..];
+            attrs.push((line[..i].to_vec(), value.strip_prefix(b" ").unwrap_or(value).to_vec()));
+        }
+        pos = next;
+    }
+    finish(&mut attrs, &buf[start..]);
+
+    sections
+}
+
+/// Checks every entry against `META-INF/MANIFEST.MF`, the manifest against each
+/// signer's `.SF` file and the `.SF` file against its PKCS#7 signature.
+pub(crate) fn verify_v1<R: Read + Seek>(archive: &mut ZipArchive<R>) -> ExtResult<SchemeVerification> {
+    let blocks = signature_blocks(archive);
+    let mut result = SchemeVerification {
+        signed: !blocks.is_empty(),
+        ..Default::default()
+    };
+    if blocks.is_empty() {
+        return Ok(result);
+    }
+
+    let manifest = match read_entry(archive, MANIFEST_PATH) {
+        Ok(manifest) => manifest,
+        Err(err) => {
+            result.errors.push(format!("{}: {}", MANIFEST_PATH, err));
+            return Ok(result);
+        }
+    };
+    let sections = parse_sections(&manifest);
+    let entries = sections.iter()
+        .skip(1)
+        .filter_map(|s| s.get("Name").map(|name| (name, s)))
+        .collect::<Vec<(&str, &Section)>>();
+
+    for (name, block) in blocks {
+        let sf_path = format!("META-INF/{}.SF", name);
+        let sf = match read_entry(archive, &sf_path) {
+            Ok(sf) => sf,
+            Err(err) => {
+                result.errors.push(format!("{}: {}: {}", name, sf_path, err));
+                continue;
+            }
+        };
+        let signature = read_entry(archive, &block)?;
+        if let Err(err) = verify_signature_block(&signature, &sf) {
+            result.errors.push(format!("{}: {}", name, err));
+        }
+        for err in verify_signature_file(&sf, &manifest, &entries) {
+            result.errors.push(format!("{}: {}", name, err));
+        }
+    }
+
+    let protected = entries.iter().map(|(name, _)| *name).collect::<HashSet<&str>>();
+    let files = archive.file_names()
+        .filter(|n| !n.ends_with('/') && *n != MANIFEST_PATH && !SIGNATURE_FILE_PATH.is_match(n))
+        .map(ToString::to_string)
+        .collect::<Vec<String>>();
+    for file in &files {
+        if !protected.contains(file.as_str()) {
+            result.errors.push(format!("{} is not protected by the manifest", file));
+        }
+    }
+
+    for (name, section) in &entries {
+        let (algorithm, expected) = match section.digest("-Digest") {
+            Some(digest) => digest,
+            None => {
+                result.errors.push(format!("{} has no supported digest in the manifest", name));
+                continue;
+            }
+        };
+        match read_entry(archive, name) {
+            Ok(data) if algorithm.digest(&data) == expected => {}
+            Ok(_) => result.errors.push(format!("{} does not match its {} digest in the manifest", name, algorithm.name())),
+            Err(err) => result.errors.push(format!("{}: {}", name, err)),
+        }
+    }
+
+    Ok(result)
+}
+
+/// Checks the `.SF` file digests of the whole manifest, or of each of its sections.
+fn verify_signature_file(sf: &[u8], manifest: &[u8], entries: &[(&str, &Section)]) -> Vec<String> {
+    let sections = parse_sections(sf);
+    let whole = sections.first()
+        .and_then(|main| main.digest("-Digest-Manifest"));
+    if let Some((algorithm, expected)) = &whole {
+        if algorithm.digest(manifest) == *expected {
+            return vec![];
+        }
+    }
+
+    let mut errors = vec![];
+    for section in sections.iter().skip(1) {
+        let name = match section.get("Name") {
+            Some(name) => name,
+            None => continue,
+        };
+        let digest = section.digest("-Digest");
+        let entry = entries.iter().find(|(n, _)| *n == name);
+        match (digest, entry) {
+            (Some((algorithm, expected)), Some((_, entry))) if algorithm.digest(entry.raw) == expected => {}
+            (_, None) => errors.push(format!("{} is in the signature file but not in the manifest", name)),
+            _ => errors.push(format!("manifest section of {} does not match the signature file", name)),
+        }
+    }
+    if whole.is_none() && sections.len() < 2 {
+        errors.push("signature file has no manifest digests".to_string());
+    }
+    errors
+}
+
+/// Checks the PKCS#7 signature of a `.SF` file.
+fn verify_signature_block(block: &[u8], sf: &[u8]) -> Result<(), String> {
+    let signed_data = SignedData::parse(block).map_err(|e| e.to_string())?;
+    let signer = signed_data.signers.first().ok_or("no signer")?;
+    let cert = signed_data.signer_certificate(signer)
+        .ok_or("signing certificate not found")
+        .and_then(|der| Certificate::from_der(der).map_err(|_| "malformed signing certificate"))?;
+    let algorithm = DigestAlgorithm::from_oid(&signer.digest_algorithm)
+        .ok_or_else(|| format!("unsupported digest algorithm {}", signer.digest_algorithm))?;
+
+    let digest = match signer.signed_attrs {
+        Some(attrs) => {
+            if signer.message_digest().map_err(|e| e.to_string())? != Some(&algorithm.digest(sf)[..]) {
+                return Err("signature file does not match the signed message digest".into());
+            }
+            // The signature covers the attributes re-tagged as a SET OF.
+            let mut attrs = attrs.raw.to_vec();
+            attrs[0] = TAG_SET;
+            algorithm.digest(&attrs)
+        }
+        None => algorithm.digest(sf),
+    };
+    let padding = match signer.signature_algorithm.as_str() {
+        OID_RSASSA_PSS => RsaPadding::Pss,
+        _ => RsaPadding::Pkcs1,
+    };
+    verify_prehashed(&cert.public_key, algorithm, &digest, signer.signature, padding)
+}
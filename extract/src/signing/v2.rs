@@ -0,0 +1,261 @@
+use core::fmt;
+use std::collections::HashMap;
+use crate::{
+    bytes::ByteReader,
+    error::ExtResult,
+};
+use super::{
+    block::{prefixed, sequence, invalid},
+    cert::Certificate,
+    crypto::{verify_prehashed, DigestAlgorithm, RsaPadding},
+};
+
+const PROOF_OF_ROTATION_ATTR_ID: u32 = 0x3ba0_6f8c;
+
+/// A signer from an APK Signature Scheme v2, v3 or v3.1 block.
+#[derive(Clone, Debug)]
+pub struct ApkSigner {
+    /// The signing certificate first, followed by the rest of the chain.
+    pub certificates: Vec<Certificate>,
+    /// DER encoded SubjectPublicKeyInfo.
+    pub public_key: Vec<u8>,
+    /// `(signature algorithm id, content digest)` pairs.
+    pub digests: Vec<(u32, Vec<u8>)>,
+    /// `(signature algorithm id, signature over signed_data)` pairs.
+    pub signatures: Vec<(u32, Vec<u8>)>,
+    /// SDK range of a v3 signer; `None` for v2.
+    pub min_sdk: Option<u32>,
+    pub max_sdk: Option<u32>,
+    /// v3 proof-of-rotation, oldest certificate first.
+    pub lineage: Vec<LineageNode>,
+    pub signed_data: Vec<u8>,
+}
+
+/// One level of a v3 signing certificate lineage.
+#[derive(Clone, Debug)]
+pub struct LineageNode {
+    pub certificate: Certificate,
+    pub flags: u32,
+    /// Algorithm the previous certificate signed this level with, `0` for the first one.
+    pub signature_algorithm: u32,
+    pub signature: Vec<u8>,
+    pub signed_data: Vec<u8>,
+}
+
+impl LineageNode {
+    pub const FLAG_INSTALLED_DATA: u32 = 1;
+    pub const FLAG_SHARED_USER_ID: u32 = 2;
+    pub const FLAG_PERMISSION: u32 = 4;
+    pub const FLAG_ROLLBACK: u32 = 8;
+    pub const FLAG_AUTH: u32 = 16;
+}
+
+/// Parses the value of a v2 (`v3 == false`) or v3/v3.1 signature scheme block.
+pub(crate) fn parse_signers(block: &[u8], v3: bool) -> ExtResult<Vec<ApkSigner>> {
+    let mut r = ByteReader::at(block, 0);
+    let signers = sequence(prefixed(&mut r)?)?;
+    if signers.is_empty() {
+        return Err(invalid("no signers"));
+    }
+    signers.into_iter()
+        .map(|signer| parse_signer(signer, v3))
+        .collect()
+}
+
+fn parse_signer(buf: &[u8], v3: bool) -> ExtResult<ApkSigner> {
+    let mut r = ByteReader::at(buf, 0);
+    let signed_data = prefixed(&mut r)?;
+    let (min_sdk, max_sdk) = if v3 {
+        (Some(r.u32()?), Some(r.u32()?))
+    } else {
+        (None, None)
+    };
+    let signatures = sequence(prefixed(&mut r)?)?
+        .into_iter()
+        .map(algorithm_and_value)
+        .collect::<ExtResult<Vec<(u32, Vec<u8>)>>>()?;
+    let public_key = prefixed(&mut r)?.to_vec();
+
+    let mut data = ByteReader::at(signed_data, 0);
+    let digests = sequence(prefixed(&mut data)?)?
+        .into_iter()
+        .map(algorithm_and_value)
+        .collect::<ExtResult<Vec<(u32, Vec<u8>)>>>()?;
+    let certificates = sequence(prefixed(&mut data)?)?
+        .into_iter()
+        .map(Certificate::from_der)
+        .collect::<ExtResult<Vec<Certificate>>>()?;
+    if v3 {
+        // The signed copy of the SDK range; it must match the unsigned one.
+        if (Some(data.u32()?), Some(data.u32()?)) != (min_sdk, max_sdk) {
+            return Err(invalid("signed and unsigned SDK ranges differ"));
+        }
+    }
+
+    let mut lineage = vec![];
+    for attr in sequence(prefixed(&mut data)?)? {
+        let mut a = ByteReader::at(attr, 0);
+        if a.u32()? == PROOF_OF_ROTATION_ATTR_ID {
+            lineage = parse_lineage(a.bytes(a.remaining())?)?;
+        }
+    }
+
+    Ok(ApkSigner {
+        certificates,
+        public_key,
+        digests,
+        signatures,
+        min_sdk,
+        max_sdk,
+        lineage,
+        signed_data: signed_data.to_vec(),
+    })
+}
+
+fn parse_lineage(buf: &[u8]) -> ExtResult<Vec<LineageNode>> {
+    let mut r = ByteReader::at(buf, 0);
+    let _version = r.u32()?;
+    let mut nodes = vec![];
+
+    for node in sequence(r.bytes(r.remaining())?)? {
+        let mut n = ByteReader::at(node, 0);
+        let signed_data = prefixed(&mut n)?;
+        let flags = n.u32()?;
+        let signature_algorithm = n.u32()?;
+        let signature = prefixed(&mut n)?.to_vec();
+
+        let mut data = ByteReader::at(signed_data, 0);
+        let certificate = Certificate::from_der(prefixed(&mut data)?)?;
+
+        nodes.push(LineageNode {
+            certificate,
+            flags,
+            signature_algorithm,
+            signature,
+            signed_data: signed_data.to_vec(),
+        });
+    }
+
+    Ok(nodes)
+}
+
+fn algorithm_and_value(buf: &[u8]) -> ExtResult<(u32, Vec<u8>)> {
+    let mut r = ByteReader::at(buf, 0);
+    let algorithm = r.u32()?;
+    Ok((algorithm, prefixed(&mut r)?.to_vec()))
+}
+
+/// Name of an APK signature algorithm id.
+pub fn algorithm_name(id: u32) -> &'static str {
+    match id {
+        0x0101 => "RSASSA-PSS with SHA2-256",
+        0x0102 => "RSASSA-PSS with SHA2-512",
+        0x0103 => "RSASSA-PKCS1-v1_5 with SHA2-256",
+        0x0104 => "RSASSA-PKCS1-v1_5 with SHA2-512",
+        0x0201 => "ECDSA with SHA2-256",
+        0x0202 => "ECDSA with SHA2-512",
+        0x0301 => "DSA with SHA2-256",
+        0x0421 => "RSASSA-PKCS1-v1_5 with SHA2-256 (verity)",
+        0x0423 => "ECDSA with SHA2-256 (verity)",
+        0x0425 => "DSA with SHA2-256 (verity)",
+        _ => "unknown",
+    }
+}
+
+/// How an APK signature algorithm signs, and how it digests the APK contents.
+/// The content digest is `None` for verity algorithms, whose digest is a Merkle
+/// tree root rather than the chunked digest.
+pub(crate) fn algorithm_params(id: u32) -> Option<(DigestAlgorithm, RsaPadding, Option<DigestAlgorithm>)> {
+    use DigestAlgorithm::{Sha256, Sha512};
+    match id {
+        0x0101 => Some((Sha256, RsaPadding::Pss, Some(Sha256))),
+        0x0102 => Some((Sha512, RsaPadding::Pss, Some(Sha512))),
+        0x0103 | 0x0201 | 0x0301 => Some((Sha256, RsaPadding::Pkcs1, Some(Sha256))),
+        0x0104 | 0x0202 => Some((Sha512, RsaPadding::Pkcs1, Some(Sha512))),
+        0x0421 | 0x0423 | 0x0425 => Some((Sha256, RsaPadding::Pkcs1, None)),
+        _ => None,
+    }
+}
+
+impl ApkSigner {
+    /// Checks the signatures over the signed data, the content digests against
+    /// `content_digests` and the proof-of-rotation chain. Returns the problems found.
+    pub(crate) fn verify(&self, content_digests: &HashMap<DigestAlgorithm, Vec<u8>>) -> Vec<String> {
+        let mut errors = vec![];
+
+        let signatures = self.signatures.iter()
+            .filter_map(|(id, signature)| algorithm_params(*id).map(|params| (*id, params, signature)))
+            .collect::<Vec<_>>();
+        if signatures.is_empty() {
+            errors.push("no supported signature algorithms".to_string());
+        }
+        for (id, (algorithm, padding, _), signature) in signatures {
+            let digest = algorithm.digest(&self.signed_data);
+            if let Err(err) = verify_prehashed(&self.public_key, algorithm, &digest, signature, padding) {
+                errors.push(format!("{}: {}", algorithm_name(id), err));
+            }
+        }
+
+        let signed = self.signatures.iter().map(|(id, _)| *id).collect::<Vec<u32>>();
+        let digested = self.digests.iter().map(|(id, _)| *id).collect::<Vec<u32>>();
+        if signed != digested {
+            errors.push("signature algorithms do not match the digest algorithms".to_string());
+        }
+
+        for (id, expected) in &self.digests {
+            let actual = algorithm_params(*id)
+                .and_then(|(_, _, content)| content)
+                .and_then(|content| content_digests.get(&content));
+            if actual.is_some_and(|actual| actual != expected) {
+                errors.push(format!("{}: APK contents do not match the signed digest", algorithm_name(*id)));
+            }
+        }
+
+        match self.certificates.first() {
+            Some(cert) if cert.public_key != self.public_key => {
+                errors.push("public key does not match the signing certificate".to_string());
+            }
+            Some(_) => {}
+            None => errors.push("no certificates".to_string()),
+        }
+
+        for (level, pair) in self.lineage.windows(2).enumerate() {
+            let (previous, node) = (&pair[0], &pair[1]);
+            let result = match algorithm_params(node.signature_algorithm) {
+                Some((algorithm, padding, _)) => {
+                    let digest = algorithm.digest(&node.signed_data);
+                    verify_prehashed(&previous.certificate.public_key, algorithm, &digest, &node.signature, padding)
+                }
+                None => Err(format!("unsupported signature algorithm 0x{:04x}", node.signature_algorithm)),
+            };
+            if let Err(err) = result {
+                errors.push(format!("lineage #{}: {}", level + 2, err));
+            }
+        }
+        if let Some(last) = self.lineage.last() {
+            if self.certificates.first() != Some(&last.certificate) {
+                errors.push("lineage does not end with the signing certificate".to_string());
+            }
+        }
+
+        errors
+    }
+}
+
+impl fmt::Display for LineageNode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let flags = [
+            (Self::FLAG_INSTALLED_DATA, "installed data"),
+            (Self::FLAG_SHARED_USER_ID, "shared UID"),
+            (Self::FLAG_PERMISSION, "permission"),
+            (Self::FLAG_ROLLBACK, "rollback"),
+            (Self::FLAG_AUTH, "auth"),
+        ]
+        .iter()
+        .filter(|(flag, _)| self.flags & flag != 0)
+        .map(|(_, name)| *name)
+        .collect::<Vec<&str>>();
+
+        write!(f, "{} [{}]", self.certificate.subject, flags.join(", "))
+    }
+}
@@ -0,0 +1,63 @@
+use crate::{
+    bytes::ByteReader,
+    error::ExtResult,
+};
+use super::{
+    block::{prefixed, invalid},
+    cert::Certificate,
+};
+
+/// An APK Signature Scheme v4 signature, stored next to the APK as `<apk>.idsig`.
+#[derive(Clone, Debug)]
+pub struct V4Signature {
+    pub version: u32,
+    pub hash_algorithm: u32,
+    pub log2_block_size: u8,
+    pub salt: Vec<u8>,
+    pub root_hash: Vec<u8>,
+    /// Digest of the v2/v3 signer this signature is bound to.
+    pub apk_digest: Vec<u8>,
+    pub certificate: Certificate,
+    pub additional_data: Vec<u8>,
+    pub public_key: Vec<u8>,
+    pub signature_algorithm: u32,
+    pub signature: Vec<u8>,
+}
+
+impl V4Signature {
+    pub fn parse(buf: &[u8]) -> ExtResult<V4Signature> {
+        let mut r = ByteReader::at(buf, 0);
+        let version = r.u32()?;
+        if version != 2 {
+            return Err(invalid("unsupported v4 signature version"));
+        }
+
+        let mut hashing = ByteReader::at(prefixed(&mut r)?, 0);
+        let hash_algorithm = hashing.u32()?;
+        let log2_block_size = hashing.u8()?;
+        let salt = prefixed(&mut hashing)?.to_vec();
+        let root_hash = prefixed(&mut hashing)?.to_vec();
+
+        let mut signing = ByteReader::at(prefixed(&mut r)?, 0);
+        let apk_digest = prefixed(&mut signing)?.to_vec();
+        let certificate = Certificate::from_der(prefixed(&mut signing)?)?;
+        let additional_data = prefixed(&mut signing)?.to_vec();
+        let public_key = prefixed(&mut signing)?.to_vec();
+        let signature_algorithm = signing.u32()?;
+        let signature = prefixed(&mut signing)?.to_vec();
+
+        Ok(V4Signature {
+            version,
+            hash_algorithm,
+            log2_block_size,
+            salt,
+            root_hash,
+            apk_digest,
+            certificate,
+            additional_data,
+            public_key,
+            signature_algorithm,
+            signature,
+        })
+    }
+}
@@ -0,0 +1,180 @@
+use core::fmt;
+use std::{
+    collections::{hash_map::Entry, HashMap},
+    io::{Read, Seek, SeekFrom},
+};
+use zip::ZipArchive;
+use crate::error::ExtResult;
+use super::{
+    block::{
+        find_zip_sections, find_signing_block, ZipSections,
+        APK_SIGNATURE_SCHEME_V2_BLOCK_ID, APK_SIGNATURE_SCHEME_V3_BLOCK_ID, APK_SIGNATURE_SCHEME_V31_BLOCK_ID,
+    },
+    crypto::DigestAlgorithm,
+    v1::verify_v1,
+    v2::{self, algorithm_params, ApkSigner},
+};
+
+const CHUNK_SIZE: u64 = 1024 * 1024;
+
+/// Outcome of verifying one signature scheme.
+#[derive(Clone, Debug, Default)]
+pub struct SchemeVerification {
+    /// Whether the APK carries a signature for this scheme at all.
+    pub signed: bool,
+    pub errors: Vec<String>,
+}
+
+impl SchemeVerification {
+    pub fn verified(&self) -> bool {
+        self.signed && self.errors.is_empty()
+    }
+}
+
+/// Result of checking every signature of an APK against its contents.
+#[derive(Clone, Debug, Default)]
+pub struct Verification {
+    pub v1: SchemeVerification,
+    pub v2: SchemeVerification,
+    pub v3: SchemeVerification,
+    pub v31: SchemeVerification,
+}
+
+impl Verification {
+    fn schemes(&self) -> [(&'static str, &'static str, &SchemeVerification); 4] {
+        [
+            ("v1", "JAR signing", &self.v1),
+            ("v2", "APK Signature Scheme v2", &self.v2),
+            ("v3", "APK Signature Scheme v3", &self.v3),
+            ("v3.1", "APK Signature Scheme v3.1", &self.v31),
+        ]
+    }
+
+    /// True when the APK is signed and every scheme present verifies.
+    pub fn verified(&self) -> bool {
+        let schemes = self.schemes();
+        schemes.iter().any(|(_, _, s)| s.signed)
+            && schemes.iter().all(|(_, _, s)| !s.signed || s.verified())
+    }
+}
+
+/// Verifies the v1 signature of every entry and the v2, v3 and v3.1 signatures
+/// over the ZIP sections.
+pub fn verify_apk<R: Read + Seek>(reader: R) -> ExtResult<Verification> {
+    let mut archive = ZipArchive::new(reader)?;
+    let mut verification = Verification {
+        v1: verify_v1(&mut archive)?,
+        ..Default::default()
+    };
+
+    let mut reader = archive.into_inner();
+    let sections = find_zip_sections(&mut reader)?;
+    let block = match find_signing_block(&mut reader, &sections)? {
+        Some(block) => block,
+        None => return Ok(verification),
+    };
+
+    let parse = |id, v3| block.get(id).map(|value| v2::parse_signers(value, v3));
+    let v2 = parse(APK_SIGNATURE_SCHEME_V2_BLOCK_ID, false);
+    let v3 = parse(APK_SIGNATURE_SCHEME_V3_BLOCK_ID, true);
+    let v31 = parse(APK_SIGNATURE_SCHEME_V31_BLOCK_ID, true);
+
+    let mut content_digests = HashMap::new();
+    for signer in [&v2, &v3, &v31].into_iter().flatten().flatten().flatten() {
+        for (id, _) in &signer.digests {
+            if let Some((_, _, Some(algorithm))) = algorithm_params(*id) {
+                if let Entry::Vacant(entry) = content_digests.entry(algorithm) {
+                    entry.insert(content_digest(&mut reader, algorithm, &sections, block.offset)?);
+                }
+            }
+        }
+    }
+
+    verification.v2 = verify_signers(v2, &content_digests);
+    verification.v3 = verify_signers(v3, &content_digests);
+    verification.v31 = verify_signers(v31, &content_digests);
+
+    Ok(verification)
+}
+
+fn verify_signers(
+    signers: Option<ExtResult<Vec<ApkSigner>>>,
+    content_digests: &HashMap<DigestAlgorithm, Vec<u8>>,
+) -> SchemeVerification {
+    let errors = match signers {
+        None => return SchemeVerification::default(),
+        Some(Err(err)) => vec![err.to_string()],
+        Some(Ok(signers)) => signers.iter()
+            .enumerate()
+            .flat_map(|(i, signer)| {
+                signer.verify(content_digests)
+                    .into_iter()
+                    .map(move |err| format!("Signer #{}: {}", i + 1, err))
+            })
+            .collect(),
+    };
+    SchemeVerification { signed: true, errors }
+}
+
+/// The chunked digest of the entries, central directory and EOCD that v2 and
+/// v3 signers sign. The EOCD is digested as if the central directory started
+/// where the signing block does.
+fn content_digest<R: Read + Seek>(
+    reader: &mut R,
+    algorithm: DigestAlgorithm,
+    sections: &ZipSections,
+    block_offset: u64,
+) -> ExtResult<Vec<u8>> {
+    let mut eocd = sections.eocd.clone();
+    eocd[16..20].copy_from_slice(&(block_offset as u32).to_le_bytes());
+
+    let mut chunks: Vec<u8> = vec![];
+    let mut count: u32 = 0;
+    let mut chunk: Vec<u8> = vec![];
+    let mut digest_chunk = |chunk: &mut Vec<u8>| {
+        let len = (chunk.len() - 5) as u32;
+        chunk[0] = 0xa5;
+        chunk[1..5].copy_from_slice(&len.to_le_bytes());
+        chunks.extend(algorithm.digest(chunk));
+        count += 1;
+    };
+
+    for (offset, size) in [(0, block_offset), (sections.cd_offset, sections.cd_size)] {
+        reader.seek(SeekFrom::Start(offset))?;
+        let mut remaining = size;
+        while remaining > 0 {
+            let len = remaining.min(CHUNK_SIZE) as usize;
+            chunk.resize(5 + len, 0);
+            reader.read_exact(&mut chunk[5..])?;
+            digest_chunk(&mut chunk);
+            remaining -= len as u64;
+        }
+    }
+    for part in eocd.chunks(CHUNK_SIZE as usize) {
+        chunk.clear();
+        chunk.extend_from_slice(&[0; 5]);
+        chunk.extend_from_slice(part);
+        digest_chunk(&mut chunk);
+    }
+
+    let mut top = vec![0x5a];
+    top.extend_from_slice(&count.to_le_bytes());
+    top.extend(chunks);
+    Ok(algorithm.digest(&top))
+}
+
+/// Prints the outcome the way `apksigner verify -v` does.
+impl fmt::Display for Verification {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "Verifies: {}", self.verified())?;
+        for (scheme, name, result) in self.schemes() {
+            writeln!(f, "Verified using {} scheme ({}): {}", scheme, name, result.verified())?;
+        }
+        for (scheme, _, result) in self.schemes() {
+            for err in &result.errors {
+                writeln!(f, "ERROR: {}: {}", scheme, err)?;
+            }
+        }
+        Ok(())
+    }
+}
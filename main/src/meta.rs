@@ -45,6 +45,22 @@ impl MetaScreen {
                     ui.label("Build:");
                     ui.label(data.build_number);
                 });
+                if let Some(channel) = data.channel {
+                    ui.horizontal(|ui| {
+                        ui.label("Channel:");
+                        ui.label(channel.to_string());
+                    });
+                }
+                if let Some(requirements) = data.requirements {
+                    ui.horizontal(|ui| {
+                        ui.label("Minimum OS:");
+                        ui.label(requirements.minimum_os_version.unwrap_or_default());
+                    });
+                    ui.horizontal(|ui| {
+                        ui.label("Devices:");
+                        ui.label(requirements.device_families.join(", "));
+                    });
+                }
                 ui.horizontal(|ui| {
                     ui.label("Icon:");
                     match base64::decode(data.icon) {